@@ -0,0 +1,357 @@
+//! Public key type able to hold any kind of key supported by this crate.
+//!
+//! Wallet descriptors and PSBTs may contain ECDSA keys (compressed or not) as
+//! well as x-only keys. Code handling such formats generically needs a single
+//! type it can store and dispatch on, which is what [`AnyPublicKey`] provides.
+//! Where the kind of key is statically known it's still better to use the
+//! specific types.
+
+use core::fmt;
+
+use crate::hex::{DisplayHex, HexError};
+use crate::legacy::KeyFormat;
+use crate::{CompressedPublicKey, LegacyPublicKey, XOnlyPublicKey};
+
+/// Public key of any kind supported by this crate.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum AnyPublicKey {
+    /// ECDSA public key that may be serialized as uncompressed.
+    Legacy(LegacyPublicKey),
+    /// ECDSA public key that is always serialized as compressed.
+    Compressed(CompressedPublicKey),
+    /// Public key intended for Schnorr signatures (Taproot).
+    XOnly(XOnlyPublicKey),
+}
+
+/// Distinguishes kinds of keys stored in [`AnyPublicKey`].
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum KeyKind {
+    /// The key is [`AnyPublicKey::Legacy`].
+    Legacy,
+    /// The key is [`AnyPublicKey::Compressed`].
+    Compressed,
+    /// The key is [`AnyPublicKey::XOnly`].
+    XOnly,
+}
+
+impl AnyPublicKey {
+    /// Parses the key detecting its kind from the length of the input.
+    ///
+    /// 32 bytes are parsed as x-only key, 33 bytes as compressed key and 65
+    /// bytes as uncompressed legacy key. Note that compressed legacy keys are
+    /// indistinguishable from compressed keys so they are always returned as
+    /// [`Compressed`](Self::Compressed).
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, AnyPublicKeyError> {
+        match bytes.len() {
+            32 => XOnlyPublicKey::from_slice(bytes)
+                .map(AnyPublicKey::XOnly)
+                .map_err(AnyPublicKeyError::InvalidKey),
+            33 => secp256k1::PublicKey::from_slice(bytes)
+                .map(|key| AnyPublicKey::Compressed(CompressedPublicKey::from_raw(key)))
+                .map_err(AnyPublicKeyError::InvalidKey),
+            // secp256k1 also accepts hybrid keys (prefix 0x06 or 0x07) which would serialize
+            // differently so we have to reject them here.
+            65 if bytes[0] != 0x04 => Err(AnyPublicKeyError::InvalidKey(
+                secp256k1::Error::InvalidPublicKey,
+            )),
+            65 => secp256k1::PublicKey::from_slice(bytes)
+                .map(|key| {
+                    AnyPublicKey::Legacy(LegacyPublicKey::from_raw(key, KeyFormat::Uncompressed))
+                })
+                .map_err(AnyPublicKeyError::InvalidKey),
+            len => Err(AnyPublicKeyError::InvalidLength(len)),
+        }
+    }
+
+    /// Returns the kind of the contained key.
+    #[inline]
+    pub fn kind(&self) -> KeyKind {
+        match self {
+            AnyPublicKey::Legacy(_) => KeyKind::Legacy,
+            AnyPublicKey::Compressed(_) => KeyKind::Compressed,
+            AnyPublicKey::XOnly(_) => KeyKind::XOnly,
+        }
+    }
+
+    /// Serializes the key into bytes appropriate for its kind.
+    ///
+    /// X-only keys produce 32 bytes, compressed keys 33 bytes and uncompressed
+    /// keys 65 bytes.
+    pub fn serialize(&self) -> SerializedAnyPublicKey {
+        let mut data = [0u8; 65];
+        let len = match self {
+            AnyPublicKey::Legacy(key) => {
+                let serialized = key.serialize_public_key();
+                data[..serialized.len()].copy_from_slice(&serialized);
+                serialized.len()
+            }
+            AnyPublicKey::Compressed(key) => {
+                data[..33].copy_from_slice(&key.serialize_public_key());
+                33
+            }
+            AnyPublicKey::XOnly(key) => {
+                data[..32].copy_from_slice(&key.serialize());
+                32
+            }
+        };
+        // len is at most 65
+        SerializedAnyPublicKey {
+            data,
+            len: len as u8,
+        }
+    }
+}
+
+/// Returns a value displaying the key as lowercase hex.
+///
+/// All kinds of keys are rendered the same way, as their serialization
+/// returned by [`AnyPublicKey::serialize`], so x-only keys produce 64,
+/// compressed keys 66 and uncompressed keys 130 hex digits. Nothing is
+/// allocated. This is intended for user interfaces presenting keys of mixed
+/// kinds.
+pub fn fmt_key_hex(key: &AnyPublicKey) -> impl fmt::Display {
+    struct KeyHex(SerializedAnyPublicKey);
+
+    impl fmt::Display for KeyHex {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            fmt::Display::fmt(&DisplayHex(self.0.as_slice()), f)
+        }
+    }
+
+    KeyHex(key.serialize())
+}
+
+/// Parses the key from hex, detecting its kind from the length.
+///
+/// This behaves like [`AnyPublicKey::from_bytes`]. The input may optionally be
+/// prefixed with `0x` or `0X`.
+impl core::str::FromStr for AnyPublicKey {
+    type Err = AnyPublicKeyError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = crate::hex::strip_prefix(s);
+        let len = crate::hex::decoded_len(s).map_err(AnyPublicKeyError::InvalidHex)?;
+        let mut buf = [0u8; 65];
+        let buf = buf
+            .get_mut(..len)
+            .ok_or(AnyPublicKeyError::InvalidLength(len))?;
+        crate::hex::decode_to_slice(s, buf).map_err(AnyPublicKeyError::InvalidHex)?;
+        AnyPublicKey::from_bytes(buf)
+    }
+}
+
+impl From<LegacyPublicKey> for AnyPublicKey {
+    #[inline]
+    fn from(value: LegacyPublicKey) -> Self { AnyPublicKey::Legacy(value) }
+}
+
+impl From<CompressedPublicKey> for AnyPublicKey {
+    #[inline]
+    fn from(value: CompressedPublicKey) -> Self { AnyPublicKey::Compressed(value) }
+}
+
+impl From<XOnlyPublicKey> for AnyPublicKey {
+    #[inline]
+    fn from(value: XOnlyPublicKey) -> Self { AnyPublicKey::XOnly(value) }
+}
+
+/// Serialized public key of any kind.
+///
+/// Similarly to [`SerializedPublicKey`](crate::legacy::SerializedPublicKey)
+/// this holds the data on stack and provides API similar to that of an
+/// immutable `Vec`.
+#[derive(Copy, Clone)]
+pub struct SerializedAnyPublicKey {
+    data: [u8; 65],
+    // invariant: len is 32, 33 or 65
+    len: u8,
+}
+
+impl SerializedAnyPublicKey {
+    /// Returns the length of the slice.
+    ///
+    /// The returned value will be 32, 33 or 65 depending on the kind of the
+    /// key.
+    #[inline]
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize { self.len.into() }
+
+    /// Returns the serialized bytes as a slice.
+    #[inline]
+    pub fn as_slice(&self) -> &[u8] { &self.data[..self.len()] }
+}
+
+impl core::ops::Deref for SerializedAnyPublicKey {
+    type Target = [u8];
+
+    #[inline]
+    fn deref(&self) -> &Self::Target { self.as_slice() }
+}
+
+impl AsRef<[u8]> for SerializedAnyPublicKey {
+    #[inline]
+    fn as_ref(&self) -> &[u8] { self.as_slice() }
+}
+
+impl core::borrow::Borrow<[u8]> for SerializedAnyPublicKey {
+    #[inline]
+    fn borrow(&self) -> &[u8] { self.as_slice() }
+}
+
+impl PartialEq for SerializedAnyPublicKey {
+    #[inline]
+    fn eq(&self, other: &SerializedAnyPublicKey) -> bool { self.as_slice() == other.as_slice() }
+}
+
+impl Eq for SerializedAnyPublicKey {}
+
+impl core::hash::Hash for SerializedAnyPublicKey {
+    #[inline]
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        <[u8] as core::hash::Hash>::hash(self.as_slice(), state)
+    }
+}
+
+impl fmt::Debug for SerializedAnyPublicKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&DisplayHex(self.as_slice()), f)
+    }
+}
+
+/// This conversion allocates
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+impl From<SerializedAnyPublicKey> for alloc::vec::Vec<u8> {
+    #[inline]
+    fn from(value: SerializedAnyPublicKey) -> Self { value.to_vec() }
+}
+
+/// Returned when parsing [`AnyPublicKey`] fails.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum AnyPublicKeyError {
+    /// The string is not valid hex.
+    InvalidHex(HexError),
+    /// The length of the input is not 32, 33 or 65 bytes.
+    InvalidLength(usize),
+    /// The input has correct length but doesn't represent a valid key.
+    InvalidKey(secp256k1::Error),
+}
+
+impl fmt::Display for AnyPublicKeyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AnyPublicKeyError::InvalidHex(_) => f.write_str("invalid hex encoding of public key"),
+            AnyPublicKeyError::InvalidLength(len) => write!(
+                f,
+                "invalid public key length {}, the length must be 32, 33 or 65 bytes",
+                len
+            ),
+            AnyPublicKeyError::InvalidKey(_) => f.write_str("invalid public key"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+impl std::error::Error for AnyPublicKeyError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            AnyPublicKeyError::InvalidHex(error) => Some(error),
+            AnyPublicKeyError::InvalidLength(_) => None,
+            AnyPublicKeyError::InvalidKey(error) => Some(error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::format;
+
+    use secp256k1::{Secp256k1, SecretKey};
+
+    use super::*;
+
+    fn public_key() -> secp256k1::PublicKey {
+        let secret = SecretKey::from_slice(&[42; 32]).unwrap();
+        secp256k1::PublicKey::from_secret_key(&Secp256k1::signing_only(), &secret)
+    }
+
+    #[test]
+    fn serialize_parse_roundtrip() {
+        let key = public_key();
+        let keys = [
+            AnyPublicKey::Legacy(LegacyPublicKey::from_raw(key, KeyFormat::Uncompressed)),
+            AnyPublicKey::Compressed(CompressedPublicKey::from_raw(key)),
+            AnyPublicKey::XOnly(key.into()),
+        ];
+        let lengths = [65, 33, 32];
+
+        for (key, len) in keys.iter().zip(lengths) {
+            let serialized = key.serialize();
+            assert_eq!(serialized.len(), len);
+            let parsed = AnyPublicKey::from_bytes(&serialized).unwrap();
+            assert_eq!(parsed, *key);
+            assert_eq!(parsed.kind(), key.kind());
+        }
+    }
+
+    #[test]
+    fn fmt_key_hex_lengths() {
+        let key = public_key();
+        let keys = [
+            AnyPublicKey::Legacy(LegacyPublicKey::from_raw(key, KeyFormat::Uncompressed)),
+            AnyPublicKey::Compressed(CompressedPublicKey::from_raw(key)),
+            AnyPublicKey::XOnly(key.into()),
+        ];
+        let lengths = [130, 66, 64];
+
+        for (key, len) in keys.iter().zip(lengths) {
+            let hex = format!("{}", fmt_key_hex(key));
+            assert_eq!(hex.len(), len);
+            assert_eq!(hex.parse::<AnyPublicKey>().unwrap(), *key);
+        }
+    }
+
+    #[test]
+    fn compressed_legacy_parses_as_compressed() {
+        let key = LegacyPublicKey::from_raw(public_key(), KeyFormat::Compressed);
+        let serialized = AnyPublicKey::Legacy(key).serialize();
+        assert_eq!(serialized.len(), 33);
+        let parsed = AnyPublicKey::from_bytes(&serialized).unwrap();
+        assert_eq!(parsed.kind(), KeyKind::Compressed);
+    }
+
+    #[test]
+    fn parse_hex() {
+        let key = AnyPublicKey::XOnly(public_key().into());
+        let hex = "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+        let generator = hex.parse::<AnyPublicKey>().unwrap();
+        assert_eq!(generator.kind(), KeyKind::XOnly);
+        assert_eq!(
+            format!("0x{}", hex).parse::<AnyPublicKey>().unwrap(),
+            generator
+        );
+        assert_ne!(generator, key);
+
+        assert!(matches!(
+            "0x123".parse::<AnyPublicKey>(),
+            Err(AnyPublicKeyError::InvalidHex(_))
+        ));
+    }
+
+    #[test]
+    fn invalid_input() {
+        assert!(matches!(
+            AnyPublicKey::from_bytes(&[2; 34]),
+            Err(AnyPublicKeyError::InvalidLength(34))
+        ));
+
+        let mut hybrid = public_key().serialize_uncompressed();
+        hybrid[0] = 0x06 | (hybrid[64] & 1);
+        assert!(matches!(
+            AnyPublicKey::from_bytes(&hybrid),
+            Err(AnyPublicKeyError::InvalidKey(_))
+        ));
+    }
+}
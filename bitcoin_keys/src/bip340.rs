@@ -3,13 +3,17 @@
 use secp256k1::Secp256k1;
 pub use secp256k1::XOnlyPublicKey;
 
+use crate::legacy::PublicKeyParseError;
 use crate::Scalar;
 
 /// Private key intended for schnorr signatures.
 ///
 /// This type wraps [`secp256k1::SecretKey`] to prevent accidental use in ECDSA
 /// signatures. It is mostly used to sign P2TR spends or derive P2TR addresses.
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+///
+/// Comparing two keys using `==` runs in constant time so it doesn't leak
+/// information about the secret through timing.
+#[derive(Debug, Copy, Clone)]
 pub struct XOnlyPrivateKey {
     key: secp256k1::SecretKey,
 }
@@ -18,6 +22,20 @@ impl XOnlyPrivateKey {
     /// Creates the x-only private key from a generic private key
     pub fn from_raw(key: secp256k1::SecretKey) -> Self { XOnlyPrivateKey { key } }
 
+    /// Borrows the underlying secp256k1 secret key.
+    ///
+    /// **Warning:** this exposes the secret and removes the protection from
+    /// using it in ECDSA signatures.
+    #[inline]
+    pub fn as_inner(&self) -> &secp256k1::SecretKey { &self.key }
+
+    /// Returns the underlying secp256k1 secret key.
+    ///
+    /// **Warning:** this exposes the secret and removes the protection from
+    /// using it in ECDSA signatures.
+    #[inline]
+    pub fn into_inner(self) -> secp256k1::SecretKey { self.key }
+
     /// Computes public key from this private key.
     pub fn compute_public_key<C: secp256k1::Signing>(
         self,
@@ -33,20 +51,83 @@ impl XOnlyPrivateKey {
     pub fn mul_tweak(self, tweak: &Scalar) -> Result<Self, secp256k1::Error> {
         self.key.mul_tweak(tweak).map(|key| XOnlyPrivateKey { key })
     }
+
+    /// Multiplies the key by the tweak.
+    ///
+    /// As opposed to [`mul_tweak`](Self::mul_tweak) this can't fail because
+    /// the product of two non-zero scalars modulo the (prime) curve order is
+    /// never zero.
+    pub fn mul_tweak_non_zero(self, tweak: &crate::NonZeroScalar) -> Self {
+        self.mul_tweak(&tweak.get())
+            .expect("non-zero tweak can't produce zero key")
+    }
+
+    /// Signs the message using BIP-340 Schnorr signature.
+    ///
+    /// This internally computes the key pair which involves computing the
+    /// public key. If you sign multiple messages use [`XOnlyKeyPair`] instead.
+    pub fn sign<C: secp256k1::Signing>(
+        &self,
+        context: &Secp256k1<C>,
+        message: &secp256k1::Message,
+        aux_rand: &[u8; 32],
+    ) -> secp256k1::schnorr::Signature {
+        let keypair = crate::SecpKeyPair::from_secret_key(context, &self.key);
+        context.sign_schnorr_with_aux_rand(message, &keypair, aux_rand)
+    }
+}
+
+/// Constant-time comparison.
+///
+/// This relies on [`secp256k1::SecretKey`] comparison which performs a
+/// branch-free comparison of all bytes finished by a volatile read. The
+/// implementation is written out explicitly to make sure the guarantee isn't
+/// lost by accidentally changing the inner type.
+impl PartialEq for XOnlyPrivateKey {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool { self.key == other.key }
+}
+
+impl Eq for XOnlyPrivateKey {}
+
+/// Exposes the secret and removes the protection from using it in ECDSA
+/// signatures.
+impl From<XOnlyPrivateKey> for secp256k1::SecretKey {
+    #[inline]
+    fn from(value: XOnlyPrivateKey) -> Self { value.key }
 }
 
 /// Key pair intended for schnorr signatures.
 ///
 /// This type wraps [`secp256k1::KeyPair`] to prevent accidental use in ECDSA
 /// signatures. It is mostly used to sign P2TR spends or derive P2TR addresses.
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+///
+/// The [`Hash`](core::hash::Hash) implementation only hashes the public key.
+/// Hashers used by maps are generally not cryptographically secure so feeding
+/// them secret data could leak it. The public key is uniquely determined by
+/// the secret key so this is still consistent with `Eq`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
 pub struct XOnlyKeyPair {
-    key: secp256k1::KeyPair,
+    key: crate::SecpKeyPair,
 }
 
 impl XOnlyKeyPair {
     /// Creates the x-only key pair from a generic key pair
-    pub fn from_raw(key: secp256k1::KeyPair) -> Self { XOnlyKeyPair { key } }
+    pub fn from_raw(key: crate::SecpKeyPair) -> Self { XOnlyKeyPair { key } }
+
+    /// Borrows the underlying secp256k1 key pair.
+    ///
+    /// **Warning:** this exposes the secret and removes the protection from
+    /// using it in ECDSA signatures.
+    #[inline]
+    pub fn as_inner(&self) -> &crate::SecpKeyPair { &self.key }
+
+    /// Returns the underlying secp256k1 key pair.
+    ///
+    /// **Warning:** this exposes the secret and removes the protection from
+    /// using it in ECDSA signatures.
+    #[inline]
+    pub fn into_inner(self) -> crate::SecpKeyPair { self.key }
 
     /// Returns the public key.
     pub fn public_key(self) -> secp256k1::XOnlyPublicKey {
@@ -65,4 +146,663 @@ impl XOnlyKeyPair {
             .add_xonly_tweak(context, tweak)
             .map(|key| XOnlyKeyPair { key })
     }
+
+    /// Tweaks the key pair by adding the tweak given as 32 big-endian bytes.
+    ///
+    /// This is the same as [`add_tweak`](Self::add_tweak) but also validates
+    /// the tweak which is convenient when it comes straight from a hash.
+    /// Returns [`secp256k1::Error::InvalidTweak`] if the tweak is not below the
+    /// curve order.
+    pub fn add_tweak_bytes<C: secp256k1::Signing + secp256k1::Verification>(
+        self,
+        context: &Secp256k1<C>,
+        tweak: &[u8; 32],
+    ) -> Result<Self, secp256k1::Error> {
+        let tweak = Scalar::from_be_bytes(*tweak).map_err(|_| secp256k1::Error::InvalidTweak)?;
+        self.add_tweak(context, &tweak)
+    }
+}
+
+/// Only hashes the public key, see the type documentation.
+impl core::hash::Hash for XOnlyKeyPair {
+    #[inline]
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) { self.key.public_key().hash(state) }
+}
+
+/// Exposes the secret and removes the protection from using it in ECDSA
+/// signatures.
+impl From<XOnlyKeyPair> for crate::SecpKeyPair {
+    #[inline]
+    fn from(value: XOnlyKeyPair) -> Self { value.key }
+}
+
+/// Secret key material that may or may not have the public key precomputed.
+///
+/// Computing the public key from the secret key requires an expensive point
+/// multiplication. Code that accepts both private keys and key pairs can use
+/// this type to avoid it whenever the public key is already known.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum XOnlyKeyMaterial {
+    /// Only the private key is available.
+    PrivateKey(XOnlyPrivateKey),
+    /// The key pair including the precomputed public key is available.
+    KeyPair(XOnlyKeyPair),
+}
+
+impl XOnlyKeyMaterial {
+    /// Returns the private key.
+    #[inline]
+    pub fn private_key(self) -> XOnlyPrivateKey {
+        match self {
+            XOnlyKeyMaterial::PrivateKey(key) => key,
+            XOnlyKeyMaterial::KeyPair(keypair) => keypair.private_key(),
+        }
+    }
+
+    /// Returns the public key if it's available without computation.
+    #[inline]
+    pub fn cached_public_key(self) -> Option<XOnlyPublicKey> {
+        match self {
+            XOnlyKeyMaterial::PrivateKey(_) => None,
+            XOnlyKeyMaterial::KeyPair(keypair) => Some(keypair.public_key()),
+        }
+    }
+
+    /// Returns the public key, computing it only if it's not available.
+    pub fn public_key<C: secp256k1::Signing>(self, context: &Secp256k1<C>) -> XOnlyPublicKey {
+        match self {
+            XOnlyKeyMaterial::PrivateKey(key) => key.compute_public_key(context),
+            XOnlyKeyMaterial::KeyPair(keypair) => keypair.public_key(),
+        }
+    }
+
+    /// Returns the key pair, computing the public key only if it's not
+    /// available.
+    pub fn to_keypair<C: secp256k1::Signing>(self, context: &Secp256k1<C>) -> XOnlyKeyPair {
+        match self {
+            XOnlyKeyMaterial::PrivateKey(key) => {
+                XOnlyKeyPair::from_raw(crate::SecpKeyPair::from_secret_key(context, key.as_inner()))
+            }
+            XOnlyKeyMaterial::KeyPair(keypair) => keypair,
+        }
+    }
+}
+
+impl From<XOnlyPrivateKey> for XOnlyKeyMaterial {
+    #[inline]
+    fn from(value: XOnlyPrivateKey) -> Self { XOnlyKeyMaterial::PrivateKey(value) }
+}
+
+impl From<XOnlyKeyPair> for XOnlyKeyMaterial {
+    #[inline]
+    fn from(value: XOnlyKeyPair) -> Self { XOnlyKeyMaterial::KeyPair(value) }
+}
+
+/// Key pair that was not tweaked with a Taproot commitment yet.
+///
+/// This is the internal key of a P2TR output. It can not sign key-path spends
+/// directly - it has to be tweaked using [`tap_tweak`](Self::tap_tweak) first.
+/// This statically prevents a common bug of signing with the untweaked key.
+///
+/// The type requires the `bitcoin_hashes` feature which is needed to compute
+/// the tweak, otherwise there would be no checked way to obtain
+/// [`TweakedKeyPair`].
+///
+/// ```compile_fail
+/// # use bitcoin_keys::bip340::UntweakedKeyPair;
+/// # use bitcoin_keys::secp256k1::{Message, Secp256k1};
+/// fn sign(keypair: UntweakedKeyPair, message: &Message) {
+///     // untweaked key pairs can't sign
+///     keypair.sign(&Secp256k1::new(), message, &[0; 32]);
+/// }
+/// ```
+#[cfg(feature = "bitcoin_hashes")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bitcoin_hashes")))]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct UntweakedKeyPair {
+    key: crate::SecpKeyPair,
+}
+
+#[cfg(feature = "bitcoin_hashes")]
+impl UntweakedKeyPair {
+    /// Creates the untweaked key pair from a generic key pair.
+    pub fn from_raw(key: crate::SecpKeyPair) -> Self { UntweakedKeyPair { key } }
+
+    /// Returns the internal public key.
+    pub fn public_key(self) -> secp256k1::XOnlyPublicKey { self.key.x_only_public_key().0 }
+
+    /// Tweaks the key pair according to BIP-341.
+    ///
+    /// `merkle_root` is the root of the script tree or `None` if the output
+    /// has no script paths.
+    pub fn tap_tweak<C: secp256k1::Signing + secp256k1::Verification>(
+        self,
+        context: &Secp256k1<C>,
+        merkle_root: Option<[u8; 32]>,
+    ) -> Result<TweakedKeyPair, secp256k1::Error> {
+        use bitcoin_hashes::{sha256, Hash, HashEngine};
+
+        let mut engine = tagged_engine("TapTweak");
+        engine.input(&self.public_key().serialize());
+        if let Some(merkle_root) = merkle_root {
+            engine.input(&merkle_root);
+        }
+        let tweak = sha256::Hash::from_engine(engine).into_inner();
+        let tweak = Scalar::from_be_bytes(tweak).map_err(|_| secp256k1::Error::InvalidTweak)?;
+
+        self.key
+            .add_xonly_tweak(context, &tweak)
+            .map(|key| TweakedKeyPair { key })
+    }
+}
+
+#[cfg(feature = "bitcoin_hashes")]
+impl From<XOnlyKeyPair> for UntweakedKeyPair {
+    fn from(value: XOnlyKeyPair) -> Self { UntweakedKeyPair::from_raw(value.key) }
+}
+
+/// Key pair tweaked with a Taproot commitment.
+///
+/// This is the output key of a P2TR output and the only key pair type that can
+/// sign key-path spends. It can not be tweaked again.
+///
+/// ```compile_fail
+/// # use bitcoin_keys::bip340::TweakedKeyPair;
+/// # use bitcoin_keys::secp256k1::Secp256k1;
+/// fn tweak(keypair: TweakedKeyPair) {
+///     // tweaked key pairs can't be tweaked again
+///     keypair.tap_tweak(&Secp256k1::new(), None);
+/// }
+/// ```
+#[cfg(feature = "bitcoin_hashes")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bitcoin_hashes")))]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct TweakedKeyPair {
+    key: crate::SecpKeyPair,
+}
+
+#[cfg(feature = "bitcoin_hashes")]
+impl TweakedKeyPair {
+    /// Creates the tweaked key pair from a generic key pair.
+    ///
+    /// **Warning:** make sure the key pair was actually tweaked. Signing with
+    /// an untweaked key produces signatures invalid for key-path spends.
+    pub fn from_raw(key: crate::SecpKeyPair) -> Self { TweakedKeyPair { key } }
+
+    /// Returns the output public key.
+    pub fn public_key(self) -> secp256k1::XOnlyPublicKey { self.key.x_only_public_key().0 }
+
+    /// Signs the message for a key-path spend.
+    pub fn sign<C: secp256k1::Signing>(
+        &self,
+        context: &Secp256k1<C>,
+        message: &secp256k1::Message,
+        aux_rand: &[u8; 32],
+    ) -> secp256k1::schnorr::Signature {
+        context.sign_schnorr_with_aux_rand(message, &self.key, aux_rand)
+    }
+}
+
+/// X-only public key ordered and hashed by its serialization.
+///
+/// The ordering and hashing of [`XOnlyPublicKey`] are defined by the secp256k1
+/// crate which may change them in future versions and does change them in
+/// `cfg(fuzzing)` builds. This newtype pins them to the 32-byte serialization
+/// regardless of upstream changes so it's suitable for deterministic storage,
+/// e.g. as a `BTreeMap` key in descriptors.
+#[derive(Debug, Copy, Clone)]
+pub struct XOnlyKey(XOnlyPublicKey);
+
+impl XOnlyKey {
+    /// Wraps the x-only public key.
+    #[inline]
+    pub fn from_raw(key: XOnlyPublicKey) -> Self { XOnlyKey(key) }
+
+    /// Returns the underlying x-only public key.
+    #[inline]
+    pub fn into_inner(self) -> XOnlyPublicKey { self.0 }
+
+    /// Serializes the key as 32 bytes of its x-coordinate.
+    #[inline]
+    pub fn serialize(&self) -> [u8; 32] { self.0.serialize() }
+}
+
+impl PartialEq for XOnlyKey {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool { self.serialize() == other.serialize() }
+}
+
+impl Eq for XOnlyKey {}
+
+impl PartialOrd for XOnlyKey {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> { Some(self.cmp(other)) }
+}
+
+impl Ord for XOnlyKey {
+    #[inline]
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering { self.serialize().cmp(&other.serialize()) }
+}
+
+impl core::hash::Hash for XOnlyKey {
+    #[inline]
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) { self.serialize().hash(state) }
+}
+
+impl From<XOnlyPublicKey> for XOnlyKey {
+    #[inline]
+    fn from(value: XOnlyPublicKey) -> Self { XOnlyKey(value) }
+}
+
+impl From<XOnlyKey> for XOnlyPublicKey {
+    #[inline]
+    fn from(value: XOnlyKey) -> Self { value.0 }
+}
+
+/// Displays the key as 64 lowercase hex digits.
+impl core::fmt::Display for XOnlyKey {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        core::fmt::Display::fmt(&crate::hex::DisplayHex(&self.serialize()), f)
+    }
+}
+
+/// Parses the key from 64 hex digits.
+///
+/// The input may optionally be prefixed with `0x` or `0X`.
+impl core::str::FromStr for XOnlyKey {
+    type Err = PublicKeyParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut buf = [0u8; 65];
+        let bytes = crate::legacy::decode_public_key_hex(s, &mut buf)?;
+        if bytes.len() != 32 {
+            return Err(PublicKeyParseError::InvalidLength(bytes.len()));
+        }
+        XOnlyPublicKey::from_slice(bytes)
+            .map(XOnlyKey)
+            .map_err(PublicKeyParseError::InvalidKey)
+    }
+}
+
+/// Serialized x-only public key.
+///
+/// This has the same API as
+/// [`SerializedPublicKey`](crate::legacy::SerializedPublicKey) so code handling
+/// serialized keys can treat them uniformly. It's always 32 bytes long.
+pub type SerializedXOnlyPublicKey = crate::legacy::serialized_public_key::FixedKeyArray<32>;
+
+impl From<&XOnlyPublicKey> for SerializedXOnlyPublicKey {
+    #[inline]
+    fn from(value: &XOnlyPublicKey) -> Self {
+        SerializedXOnlyPublicKey::from_array(value.serialize())
+    }
+}
+
+impl From<&XOnlyKey> for SerializedXOnlyPublicKey {
+    #[inline]
+    fn from(value: &XOnlyKey) -> Self { SerializedXOnlyPublicKey::from_array(value.serialize()) }
+}
+
+/// Constructs the x-only key from its x-coordinate as defined in BIP-340.
+///
+/// Returns an error if `x` is not a valid x-coordinate of a point on the curve.
+pub fn lift_x(x: [u8; 32]) -> Result<XOnlyPublicKey, secp256k1::Error> {
+    XOnlyPublicKey::from_slice(&x)
+}
+
+/// Constructs the full public key with even y-coordinate from the
+/// x-coordinate.
+///
+/// Returns an error if `x` is not a valid x-coordinate of a point on the curve.
+pub fn lift_x_to_full(x: [u8; 32]) -> Result<secp256k1::PublicKey, secp256k1::Error> {
+    lift_x(x).map(|key| key.public_key(secp256k1::Parity::Even))
+}
+
+/// Verifies the BIP-340 signature without requiring a context.
+///
+/// This creates a verification context in a small stack buffer so it works
+/// on `no_std` targets without an allocator or a source of randomness.
+/// Verification doesn't touch secret data so, unlike signing, it doesn't
+/// need a randomized context. libsecp256k1 always uses static precomputed
+/// tables for verification so no special build configuration is required.
+pub fn verify_no_ctx(
+    public_key: &XOnlyPublicKey,
+    message: &secp256k1::Message,
+    signature: &secp256k1::schnorr::Signature,
+) -> Result<(), secp256k1::Error> {
+    crate::stack_context::with_verification_context(|context| {
+        context.verify_schnorr(signature, message, public_key)
+    })
+}
+
+/// Computes the BIP-340 tagged hash of the message.
+///
+/// The hash is `SHA256(SHA256(tag) || SHA256(tag) || msg)`. Tagging separates
+/// the hashes used in different contexts so they can't collide. Custom
+/// commitments should use their own unique tag.
+#[cfg(feature = "bitcoin_hashes")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bitcoin_hashes")))]
+pub fn tagged_hash(tag: &str, msg: &[u8]) -> [u8; 32] {
+    use bitcoin_hashes::{sha256, Hash, HashEngine};
+
+    let mut engine = tagged_engine(tag);
+    engine.input(msg);
+    sha256::Hash::from_engine(engine).into_inner()
+}
+
+/// Returns the SHA256 engine with the tag prefix already fed in.
+#[cfg(feature = "bitcoin_hashes")]
+fn tagged_engine(tag: &str) -> bitcoin_hashes::sha256::HashEngine {
+    use bitcoin_hashes::{sha256, Hash, HashEngine};
+
+    let tag = sha256::Hash::hash(tag.as_bytes());
+    let mut engine = sha256::Hash::engine();
+    engine.input(&tag);
+    engine.input(&tag);
+    engine
+}
+
+/// Creates a key pair using the scalar as the secret key.
+///
+/// Returns [`secp256k1::Error::InvalidSecretKey`] if the scalar is zero.
+pub fn keypair_from_scalar<C: secp256k1::Signing>(
+    context: &Secp256k1<C>,
+    scalar: &Scalar,
+) -> Result<XOnlyKeyPair, secp256k1::Error> {
+    let secret = secp256k1::SecretKey::from_slice(&scalar.to_be_bytes())?;
+    Ok(XOnlyKeyPair::from_raw(crate::SecpKeyPair::from_secret_key(
+        context, &secret,
+    )))
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::hash_map::DefaultHasher;
+    use std::format;
+    use std::hash::{Hash, Hasher};
+    use std::string::ToString;
+
+    use super::*;
+
+    fn hash<T: Hash>(value: &T) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn mul_tweak_non_zero() {
+        let key = XOnlyPrivateKey::from_raw(secp256k1::SecretKey::from_slice(&[42; 32]).unwrap());
+        let tweak = crate::NonZeroScalar::new(Scalar::from_be_bytes([3; 32]).unwrap()).unwrap();
+        assert_eq!(
+            key.mul_tweak_non_zero(&tweak).into_inner(),
+            key.mul_tweak(&tweak.get()).unwrap().into_inner()
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "bitcoin_hashes")]
+    fn tagged_hash_bip340_challenge() {
+        // BIP-340 test vector 0: s * G == R + e * P
+        let context = Secp256k1::new();
+        let mut secret = [0u8; 32];
+        secret[31] = 3;
+        let public_key = secp256k1::SecretKey::from_slice(&secret)
+            .unwrap()
+            .public_key(&context);
+        let mut signature = [0u8; 64];
+        crate::hex::decode_to_slice(
+            "e907831f80848d1069a5371b402410364bdf1c5f8307b0084c55f1ce2dca8215\
+             25f66a4a85ea8b71e482a74f382d2ce5ebeee8fdb2172f477df4900d310536c0",
+            &mut signature,
+        )
+        .unwrap();
+        let mut r = [0u8; 32];
+        r.copy_from_slice(&signature[..32]);
+
+        let mut msg = [0u8; 96];
+        msg[..32].copy_from_slice(&r);
+        msg[32..64].copy_from_slice(&XOnlyPublicKey::from(public_key).serialize());
+        let e = tagged_hash("BIP0340/challenge", &msg);
+
+        let left = secp256k1::SecretKey::from_slice(&signature[32..])
+            .unwrap()
+            .public_key(&context);
+        let right = lift_x_to_full(r)
+            .unwrap()
+            .combine(
+                &public_key
+                    .mul_tweak(&context, &Scalar::from_be_bytes(e).unwrap())
+                    .unwrap(),
+            )
+            .unwrap();
+        assert_eq!(left, right);
+    }
+
+    #[test]
+    fn verify_no_ctx_bip340_vector() {
+        // BIP-340 test vector 1
+        let public_key = "dff1d77f2a671c5f36183726db2341be58feae1da2deced843240f7b502ba659"
+            .parse::<XOnlyKey>()
+            .unwrap()
+            .into_inner();
+        let mut message = [0u8; 32];
+        crate::hex::decode_to_slice(
+            "243f6a8885a308d313198a2e03707344a4093822299f31d0082efa98ec4e6c89",
+            &mut message,
+        )
+        .unwrap();
+        let message = secp256k1::Message::from_slice(&message).unwrap();
+        let mut signature = [0u8; 64];
+        crate::hex::decode_to_slice(
+            "6896bd60eeae296db48a229ff71dfe071bde413e6d43f917dc8dcf8c78de3341\
+             8906d11ac976abccb20b091292bff4ea897efcb639ea871cfa95f6de339e4b0a",
+            &mut signature,
+        )
+        .unwrap();
+        let valid = secp256k1::schnorr::Signature::from_slice(&signature).unwrap();
+        assert_eq!(verify_no_ctx(&public_key, &message, &valid), Ok(()));
+
+        signature[63] ^= 1;
+        let invalid = secp256k1::schnorr::Signature::from_slice(&signature).unwrap();
+        assert!(verify_no_ctx(&public_key, &message, &invalid).is_err());
+    }
+
+    #[test]
+    fn serialized_x_only() {
+        let hex = "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+        let key = hex.parse::<XOnlyKey>().unwrap();
+        let serialized = SerializedXOnlyPublicKey::from(&key);
+        assert_eq!(serialized.len(), 32);
+        assert_eq!(*serialized, key.serialize());
+        assert_eq!(
+            serialized,
+            SerializedXOnlyPublicKey::from(&key.into_inner())
+        );
+        assert_eq!(format!("{:?}", serialized), hex);
+
+        let mut iter = serialized.into_iter();
+        assert_eq!(iter.nth(31), Some(0x98));
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn key_material() {
+        let context = Secp256k1::signing_only();
+        let keypair = crate::SecpKeyPair::from_seckey_slice(&context, &[42; 32]).unwrap();
+        let keypair = XOnlyKeyPair::from_raw(keypair);
+        let private = keypair.private_key();
+
+        let cached = XOnlyKeyMaterial::from(keypair);
+        let uncached = XOnlyKeyMaterial::from(private);
+        assert_eq!(cached.cached_public_key(), Some(keypair.public_key()));
+        assert_eq!(uncached.cached_public_key(), None);
+        for material in [cached, uncached] {
+            assert_eq!(material.private_key(), private);
+            assert_eq!(material.public_key(&context), keypair.public_key());
+            assert_eq!(material.to_keypair(&context), keypair);
+        }
+    }
+
+    #[test]
+    fn add_tweak_bytes() {
+        let context = Secp256k1::new();
+        let keypair = crate::SecpKeyPair::from_seckey_slice(&context, &[42; 32]).unwrap();
+        let keypair = XOnlyKeyPair::from_raw(keypair);
+
+        let tweak = [7; 32];
+        let scalar = Scalar::from_be_bytes(tweak).unwrap();
+        assert_eq!(
+            keypair.add_tweak_bytes(&context, &tweak),
+            keypair.add_tweak(&context, &scalar)
+        );
+        assert_eq!(
+            keypair.add_tweak_bytes(&context, &[0xff; 32]),
+            Err(secp256k1::Error::InvalidTweak)
+        );
+    }
+
+    #[test]
+    fn keypair_hashes_public_key_only() {
+        let context = Secp256k1::signing_only();
+        let keypair = crate::SecpKeyPair::from_seckey_slice(&context, &[42; 32]).unwrap();
+        let xonly = XOnlyKeyPair::from_raw(keypair);
+
+        assert_eq!(hash(&xonly), hash(&keypair.public_key()));
+        assert_eq!(hash(&xonly), hash(&XOnlyKeyPair::from_raw(keypair)));
+    }
+
+    #[test]
+    fn private_key_eq() {
+        let a = XOnlyPrivateKey::from_raw(secp256k1::SecretKey::from_slice(&[42; 32]).unwrap());
+        let b = XOnlyPrivateKey::from_raw(secp256k1::SecretKey::from_slice(&[42; 32]).unwrap());
+        let c = XOnlyPrivateKey::from_raw(secp256k1::SecretKey::from_slice(&[43; 32]).unwrap());
+
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn private_key_signs() {
+        let context = Secp256k1::new();
+        let key = XOnlyPrivateKey::from_raw(secp256k1::SecretKey::from_slice(&[42; 32]).unwrap());
+        let message = secp256k1::Message::from_slice(&[1; 32]).unwrap();
+
+        let signature = key.sign(&context, &message, &[7; 32]);
+        context
+            .verify_schnorr(&signature, &message, &key.compute_public_key(&context))
+            .unwrap();
+    }
+
+    #[test]
+    fn xonly_key_order_and_parse() {
+        let hex = "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+        let generator = hex.parse::<XOnlyKey>().unwrap();
+        assert_eq!(format!("{}", generator), hex);
+        assert_eq!(format!("0x{}", hex).parse::<XOnlyKey>().unwrap(), generator);
+
+        let keypair =
+            crate::SecpKeyPair::from_seckey_slice(&Secp256k1::signing_only(), &[42; 32]).unwrap();
+        let other = XOnlyKey::from(keypair.x_only_public_key().0);
+        assert_eq!(other.to_string().parse::<XOnlyKey>().unwrap(), other);
+        assert_eq!(
+            generator.cmp(&other),
+            generator.serialize().cmp(&other.serialize())
+        );
+
+        let mut map = std::collections::BTreeMap::new();
+        map.insert(other, 1);
+        map.insert(generator, 0);
+        let keys = map
+            .keys()
+            .map(XOnlyKey::serialize)
+            .collect::<std::vec::Vec<_>>();
+        assert!(keys[0] < keys[1]);
+
+        assert!(matches!(
+            "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798"
+                .parse::<XOnlyKey>(),
+            Err(PublicKeyParseError::InvalidLength(33))
+        ));
+    }
+
+    #[test]
+    fn lift_x_validates() {
+        let key = crate::SecpKeyPair::from_seckey_slice(&Secp256k1::signing_only(), &[42; 32])
+            .unwrap()
+            .public_key();
+        let (xonly, _) = key.x_only_public_key();
+
+        assert_eq!(lift_x(xonly.serialize()), Ok(xonly));
+        let full = lift_x_to_full(xonly.serialize()).unwrap();
+        assert_eq!(full.serialize()[0], 0x02);
+        assert_eq!(full.x_only_public_key().0, xonly);
+
+        // x = 5 is not a valid x-coordinate
+        let mut x = [0u8; 32];
+        x[31] = 5;
+        assert!(lift_x(x).is_err());
+        assert!(lift_x_to_full(x).is_err());
+    }
+
+    #[test]
+    fn scalar_keypair_signs() {
+        let context = Secp256k1::new();
+        let scalar = Scalar::from_be_bytes([42; 32]).unwrap();
+        let keypair = keypair_from_scalar(&context, &scalar).unwrap();
+        let message = secp256k1::Message::from_slice(&[1; 32]).unwrap();
+
+        let signature = context.sign_schnorr_no_aux_rand(&message, &keypair.key);
+        context
+            .verify_schnorr(&signature, &message, &keypair.public_key())
+            .unwrap();
+
+        assert_eq!(
+            keypair_from_scalar(&context, &Scalar::ZERO),
+            Err(secp256k1::Error::InvalidSecretKey)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "bitcoin_hashes")]
+    fn tap_tweak_bip341_vector() {
+        let context = Secp256k1::new();
+        let secret = [
+            0x6b, 0x97, 0x3d, 0x88, 0x83, 0x8f, 0x27, 0x36, 0x6e, 0xd6, 0x1c, 0x9a, 0xd6, 0x36,
+            0x76, 0x63, 0x04, 0x5c, 0xb4, 0x56, 0xe2, 0x83, 0x35, 0xc1, 0x09, 0xe3, 0x07, 0x17,
+            0xae, 0x0c, 0x6b, 0xaa,
+        ];
+        let internal = [
+            0xd6, 0x88, 0x9c, 0xb0, 0x81, 0x03, 0x6e, 0x0f, 0xae, 0xfa, 0x3a, 0x35, 0x15, 0x7a,
+            0xd7, 0x10, 0x86, 0xb1, 0x23, 0xb2, 0xb1, 0x44, 0xb6, 0x49, 0x79, 0x8b, 0x49, 0x4c,
+            0x30, 0x0a, 0x96, 0x1d,
+        ];
+        let output = [
+            0x53, 0xa1, 0xf6, 0xe4, 0x54, 0xdf, 0x1a, 0xa2, 0x77, 0x6a, 0x28, 0x14, 0xa7, 0x21,
+            0x37, 0x2d, 0x62, 0x58, 0x05, 0x0d, 0xe3, 0x30, 0xb3, 0xc6, 0xd1, 0x0e, 0xe8, 0xf4,
+            0xe0, 0xdd, 0xa3, 0x43,
+        ];
+
+        let keypair = crate::SecpKeyPair::from_seckey_slice(&context, &secret).unwrap();
+        let untweaked = UntweakedKeyPair::from_raw(keypair);
+        assert_eq!(untweaked.public_key().serialize(), internal);
+        let tweaked = untweaked.tap_tweak(&context, None).unwrap();
+        assert_eq!(tweaked.public_key().serialize(), output);
+    }
+
+    #[test]
+    #[cfg(feature = "bitcoin_hashes")]
+    fn tap_tweak_sign() {
+        let context = Secp256k1::new();
+        let keypair = crate::SecpKeyPair::from_seckey_slice(&context, &[42; 32]).unwrap();
+        let untweaked = UntweakedKeyPair::from_raw(keypair);
+        let tweaked = untweaked.tap_tweak(&context, None).unwrap();
+        assert_ne!(tweaked.public_key(), untweaked.public_key());
+
+        let message = secp256k1::Message::from_slice(&[1; 32]).unwrap();
+        let signature = tweaked.sign(&context, &message, &[0; 32]);
+        context
+            .verify_schnorr(&signature, &message, &tweaked.public_key())
+            .unwrap();
+    }
 }
@@ -0,0 +1,73 @@
+//! Helpers for ECDSA signatures.
+//!
+//! Bitcoin requires ECDSA signatures to use the lower of the two possible `S`
+//! values (BIP-146), otherwise they're non-standard and not relayed. Signatures
+//! produced by secp256k1 are always low-S but signatures received from other
+//! parties may not be. The functions in this module check and enforce this.
+
+use secp256k1::ecdsa::Signature;
+
+/// Returns `true` if the `S` value of the signature is in the lower half of
+/// the curve order.
+#[inline]
+pub fn is_low_s(signature: &Signature) -> bool {
+    let mut normalized = *signature;
+    normalized.normalize_s();
+    normalized == *signature
+}
+
+/// Replaces the `S` value of the signature with the low one if it's high.
+///
+/// The resulting signature is still valid for the same message and key.
+#[inline]
+pub fn normalize_signature(signature: &mut Signature) { signature.normalize_s(); }
+
+/// Returns the signature with low `S` value.
+///
+/// This is the same as [`normalize_signature`] but takes the signature by
+/// value.
+#[inline]
+pub fn ensure_low_s(mut signature: Signature) -> Signature {
+    signature.normalize_s();
+    signature
+}
+
+#[cfg(test)]
+mod test {
+    use secp256k1::{Message, Secp256k1, SecretKey};
+
+    use super::*;
+
+    // secp256k1 curve order
+    const ORDER: [u8; 32] = [
+        0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+        0xfe, 0xba, 0xae, 0xdc, 0xe6, 0xaf, 0x48, 0xa0, 0x3b, 0xbf, 0xd2, 0x5e, 0x8c, 0xd0, 0x36,
+        0x41, 0x41,
+    ];
+
+    #[test]
+    fn low_s() {
+        let context = Secp256k1::new();
+        let secret = SecretKey::from_slice(&[42; 32]).unwrap();
+        let message = Message::from_slice(&[1; 32]).unwrap();
+        let signature = context.sign_ecdsa(&message, &secret);
+        assert!(is_low_s(&signature));
+
+        // s' = n - s
+        let mut compact = signature.serialize_compact();
+        let mut borrow = 0i16;
+        for i in (32..64).rev() {
+            let diff = i16::from(ORDER[i - 32]) - i16::from(compact[i]) - borrow;
+            compact[i] = diff as u8;
+            borrow = if diff < 0 { 1 } else { 0 };
+        }
+        let mut high = Signature::from_compact(&compact).unwrap();
+        assert!(!is_low_s(&high));
+
+        assert_eq!(ensure_low_s(high), signature);
+        normalize_signature(&mut high);
+        assert_eq!(high, signature);
+        let public_key = secret.public_key(&context);
+        assert!(context.verify_ecdsa(&message, &high, &public_key).is_ok());
+    }
+}
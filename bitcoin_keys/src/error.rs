@@ -0,0 +1,143 @@
+//! Error type unifying key parsing errors.
+//!
+//! The parsers in this crate return specific errors describing exactly what
+//! can go wrong in each of them. Applications handling multiple kinds of keys
+//! often don't need that precision and can convert them all into
+//! [`KeyParseError`] using `?`.
+
+use core::fmt;
+
+use crate::any_key::AnyPublicKeyError;
+use crate::legacy::{KeyNotCompressedError, PublicKeyParseError, SecretKeyParseError};
+use crate::HexError;
+
+/// Returned when parsing any kind of key fails.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum KeyParseError {
+    /// The string is not valid hex.
+    InvalidHex(HexError),
+    /// The decoded key has invalid length.
+    InvalidLength(usize),
+    /// The secret key is zero or not below the curve order.
+    InvalidSecretKey(secp256k1::Error),
+    /// The public key is not a valid point on the curve.
+    InvalidPublicKey(secp256k1::Error),
+    /// The key is uncompressed but compressed key was required.
+    NotCompressed(KeyNotCompressedError),
+}
+
+impl fmt::Display for KeyParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            KeyParseError::InvalidHex(_) => f.write_str("invalid hex encoding of key"),
+            KeyParseError::InvalidLength(len) => write!(f, "invalid key length {}", len),
+            KeyParseError::InvalidSecretKey(_) => f.write_str("invalid secret key"),
+            KeyParseError::InvalidPublicKey(_) => f.write_str("invalid public key"),
+            KeyParseError::NotCompressed(error) => fmt::Display::fmt(error, f),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+impl std::error::Error for KeyParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            KeyParseError::InvalidHex(error) => Some(error),
+            KeyParseError::InvalidLength(_) => None,
+            KeyParseError::InvalidSecretKey(error) => Some(error),
+            KeyParseError::InvalidPublicKey(error) => Some(error),
+            // the message is already included in ours
+            KeyParseError::NotCompressed(_) => None,
+        }
+    }
+}
+
+impl From<HexError> for KeyParseError {
+    #[inline]
+    fn from(error: HexError) -> Self { KeyParseError::InvalidHex(error) }
+}
+
+impl From<KeyNotCompressedError> for KeyParseError {
+    #[inline]
+    fn from(error: KeyNotCompressedError) -> Self { KeyParseError::NotCompressed(error) }
+}
+
+impl From<PublicKeyParseError> for KeyParseError {
+    fn from(error: PublicKeyParseError) -> Self {
+        match error {
+            PublicKeyParseError::InvalidHex(error) => KeyParseError::InvalidHex(error),
+            PublicKeyParseError::InvalidLength(len) => KeyParseError::InvalidLength(len),
+            PublicKeyParseError::InvalidKey(error) => KeyParseError::InvalidPublicKey(error),
+        }
+    }
+}
+
+impl From<SecretKeyParseError> for KeyParseError {
+    fn from(error: SecretKeyParseError) -> Self {
+        match error {
+            SecretKeyParseError::InvalidLength(len) => KeyParseError::InvalidLength(len),
+            SecretKeyParseError::InvalidKey(error) => KeyParseError::InvalidSecretKey(error),
+        }
+    }
+}
+
+impl From<AnyPublicKeyError> for KeyParseError {
+    fn from(error: AnyPublicKeyError) -> Self {
+        match error {
+            AnyPublicKeyError::InvalidHex(error) => KeyParseError::InvalidHex(error),
+            AnyPublicKeyError::InvalidLength(len) => KeyParseError::InvalidLength(len),
+            AnyPublicKeyError::InvalidKey(error) => KeyParseError::InvalidPublicKey(error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use core::convert::TryFrom;
+
+    use super::*;
+    use crate::{AnyPublicKey, CompressedPrivateKey, CompressedPublicKey, LegacyPublicKey};
+
+    fn parse_all(
+        public: &str,
+        any: &str,
+        secret: &[u8],
+    ) -> Result<(CompressedPublicKey, AnyPublicKey, CompressedPrivateKey), KeyParseError> {
+        let public = public.parse::<LegacyPublicKey>()?;
+        let public = CompressedPublicKey::try_from(public)?;
+        let any = any.parse::<AnyPublicKey>()?;
+        let secret = CompressedPrivateKey::try_from(secret)?;
+        Ok((public, any, secret))
+    }
+
+    #[test]
+    fn conversions() {
+        let compressed = "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+        let uncompressed = "0479be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798\
+                            483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8";
+
+        assert!(parse_all(compressed, compressed, &[42; 32]).is_ok());
+        assert!(matches!(
+            parse_all("02zz", compressed, &[42; 32]),
+            Err(KeyParseError::InvalidHex(_))
+        ));
+        assert!(matches!(
+            parse_all(uncompressed, compressed, &[42; 32]),
+            Err(KeyParseError::NotCompressed(_))
+        ));
+        assert!(matches!(
+            parse_all(compressed, "0279", &[42; 32]),
+            Err(KeyParseError::InvalidLength(2))
+        ));
+        assert!(matches!(
+            parse_all(compressed, compressed, &[0; 32]),
+            Err(KeyParseError::InvalidSecretKey(_))
+        ));
+        assert!(matches!(
+            parse_all(compressed, compressed, &[42; 31]),
+            Err(KeyParseError::InvalidLength(31))
+        ));
+    }
+}
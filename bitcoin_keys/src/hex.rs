@@ -0,0 +1,102 @@
+//! Minimal allocation-free hex decoding used by the parsers in this crate.
+
+use core::fmt;
+
+/// Returned when a string is not a valid hex encoding.
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum HexError {
+    /// The string contains a character that is not a hex digit.
+    InvalidChar {
+        /// Position of the invalid character in the string, excluding the
+        /// `0x` prefix.
+        index: usize,
+    },
+    /// The string has odd number of hex digits.
+    OddLength,
+}
+
+impl fmt::Display for HexError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            HexError::InvalidChar { index } => {
+                write!(f, "invalid hex character at index {}", index)
+            }
+            HexError::OddLength => f.write_str("odd number of hex digits"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+impl std::error::Error for HexError {}
+
+/// Displays the bytes as lowercase hex.
+pub(crate) struct DisplayHex<'a>(pub(crate) &'a [u8]);
+
+impl fmt::Display for DisplayHex<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for b in self.0 {
+            write!(f, "{:02x}", b)?;
+        }
+        Ok(())
+    }
+}
+
+/// Removes the optional `0x` or `0X` prefix.
+pub(crate) fn strip_prefix(s: &str) -> &str {
+    s.strip_prefix("0x")
+        .or_else(|| s.strip_prefix("0X"))
+        .unwrap_or(s)
+}
+
+/// Returns the number of bytes the hex string (without prefix) decodes into.
+pub(crate) fn decoded_len(s: &str) -> Result<usize, HexError> {
+    match (s.len() / 2, s.len() % 2) {
+        (len, 0) => Ok(len),
+        _ => Err(HexError::OddLength),
+    }
+}
+
+/// Decodes the hex string (without prefix) into `buf`.
+///
+/// The length of `buf` must be equal to [`decoded_len`].
+pub(crate) fn decode_to_slice(s: &str, buf: &mut [u8]) -> Result<(), HexError> {
+    debug_assert_eq!(decoded_len(s), Ok(buf.len()));
+
+    fn digit(s: &[u8], index: usize) -> Result<u8, HexError> {
+        match s[index] {
+            c @ b'0'..=b'9' => Ok(c - b'0'),
+            c @ b'a'..=b'f' => Ok(c - b'a' + 10),
+            c @ b'A'..=b'F' => Ok(c - b'A' + 10),
+            _ => Err(HexError::InvalidChar { index }),
+        }
+    }
+
+    let s = s.as_bytes();
+    for (i, byte) in buf.iter_mut().enumerate() {
+        *byte = digit(s, i * 2)? << 4 | digit(s, i * 2 + 1)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn decode() {
+        let s = strip_prefix("0xdeadBEEF");
+        let mut buf = [0u8; 4];
+        decode_to_slice(s, &mut buf).unwrap();
+        assert_eq!(buf, [0xde, 0xad, 0xbe, 0xef]);
+
+        assert_eq!(strip_prefix("0Xab"), "ab");
+        assert_eq!(strip_prefix("ab"), "ab");
+        assert_eq!(decoded_len("abc"), Err(HexError::OddLength));
+        assert_eq!(
+            decode_to_slice("ag", &mut [0]),
+            Err(HexError::InvalidChar { index: 1 })
+        );
+    }
+}
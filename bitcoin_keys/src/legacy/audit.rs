@@ -0,0 +1,68 @@
+//! Hook observing dangerous format overrides.
+//!
+//! Recovery tools using [`force_set_format`](super::Legacy::force_set_format)
+//! or [`force_to_compressed`](super::Legacy::force_to_compressed) may want to
+//! keep an audit trail of every override. The hook registered using
+//! [`set_format_hook`] is called with the old and the new format each time one
+//! of these methods is invoked, even if the format doesn't change.
+//!
+//! The hook is global so it should be set once by the application, not by
+//! libraries.
+
+use core::sync::atomic::{AtomicPtr, Ordering};
+
+use super::KeyFormat;
+
+/// Signature of the hook, the arguments are the old and the new format.
+pub type FormatHook = fn(&KeyFormat, &KeyFormat);
+
+// Null if no hook is set, otherwise a `FormatHook` cast to a pointer.
+static HOOK: AtomicPtr<()> = AtomicPtr::new(core::ptr::null_mut());
+
+/// Sets the hook called on each format override, replacing the previous one.
+pub fn set_format_hook(hook: FormatHook) { HOOK.store(hook as *mut (), Ordering::Release); }
+
+/// Removes the hook.
+pub fn clear_format_hook() { HOOK.store(core::ptr::null_mut(), Ordering::Release); }
+
+pub(super) fn notify(old: &KeyFormat, new: &KeyFormat) {
+    let hook = HOOK.load(Ordering::Acquire);
+    if !hook.is_null() {
+        // SAFETY: non-null values are only ever stored by `set_format_hook`
+        // which casts a `FormatHook`, so this casts it back to the same type.
+        let hook = unsafe { core::mem::transmute::<*mut (), FormatHook>(hook) };
+        hook(old, new);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use core::sync::atomic::AtomicUsize;
+
+    use super::*;
+    use crate::legacy::Legacy;
+
+    static CALLS: AtomicUsize = AtomicUsize::new(0);
+
+    fn hook(old: &KeyFormat, new: &KeyFormat) {
+        assert_eq!(
+            (*old, *new),
+            (KeyFormat::Uncompressed, KeyFormat::Compressed)
+        );
+        CALLS.fetch_add(1, Ordering::Relaxed);
+    }
+
+    #[test]
+    fn hook_is_called() {
+        let key = secp256k1::SecretKey::from_slice(&[42; 32]).unwrap();
+        let mut legacy = Legacy::from_raw(key, KeyFormat::Uncompressed);
+
+        set_format_hook(hook);
+        legacy.force_set_format(KeyFormat::Compressed);
+        Legacy::from_raw(key, KeyFormat::Uncompressed).force_to_compressed();
+        clear_format_hook();
+        legacy.force_set_format(KeyFormat::Uncompressed);
+
+        assert_eq!(CALLS.load(Ordering::Relaxed), 2);
+    }
+}
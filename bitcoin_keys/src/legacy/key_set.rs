@@ -0,0 +1,148 @@
+//! Set of legacy public keys deduplicated by the point.
+
+use alloc::collections::btree_map::{self, BTreeMap};
+
+use super::{KeyFormat, Legacy};
+
+/// Set of legacy public keys ignoring their format.
+///
+/// `Eq` of [`Legacy`] takes the format into account so the same point with
+/// different formats would be stored twice in a regular set. This set
+/// deduplicates by the point only, keeping the format it was first inserted
+/// with. This is useful when merging keys from multiple sources.
+///
+/// The keys are iterated in the order of the points.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct LegacyKeySet {
+    keys: BTreeMap<secp256k1::PublicKey, KeyFormat>,
+}
+
+impl LegacyKeySet {
+    /// Creates an empty set.
+    #[inline]
+    pub fn new() -> Self { LegacyKeySet::default() }
+
+    /// Inserts the key if its point is not present yet.
+    ///
+    /// Returns `true` if the key was inserted. If the point is already present
+    /// the set is not modified, so the originally inserted format is kept.
+    pub fn insert(&mut self, key: Legacy<secp256k1::PublicKey>) -> bool {
+        match self.keys.entry(key.raw_key()) {
+            btree_map::Entry::Vacant(entry) => {
+                entry.insert(key.format());
+                true
+            }
+            btree_map::Entry::Occupied(_) => false,
+        }
+    }
+
+    /// Returns the stored key with the same point, regardless of the format.
+    #[inline]
+    pub fn get(&self, key: &secp256k1::PublicKey) -> Option<Legacy<secp256k1::PublicKey>> {
+        self.keys
+            .get(key)
+            .map(|format| Legacy::from_raw(*key, *format))
+    }
+
+    /// Returns `true` if the set contains the point.
+    #[inline]
+    pub fn contains(&self, key: &secp256k1::PublicKey) -> bool { self.keys.contains_key(key) }
+
+    /// Removes the point from the set, returning the stored key.
+    #[inline]
+    pub fn remove(&mut self, key: &secp256k1::PublicKey) -> Option<Legacy<secp256k1::PublicKey>> {
+        self.keys
+            .remove(key)
+            .map(|format| Legacy::from_raw(*key, format))
+    }
+
+    /// Returns the number of keys in the set.
+    #[inline]
+    pub fn len(&self) -> usize { self.keys.len() }
+
+    /// Returns `true` if the set contains no keys.
+    #[inline]
+    pub fn is_empty(&self) -> bool { self.keys.is_empty() }
+
+    /// Returns an iterator over the keys with their stored formats.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_> { Iter(self.keys.iter()) }
+}
+
+impl Extend<Legacy<secp256k1::PublicKey>> for LegacyKeySet {
+    fn extend<I: IntoIterator<Item = Legacy<secp256k1::PublicKey>>>(&mut self, iter: I) {
+        for key in iter {
+            self.insert(key);
+        }
+    }
+}
+
+impl core::iter::FromIterator<Legacy<secp256k1::PublicKey>> for LegacyKeySet {
+    fn from_iter<I: IntoIterator<Item = Legacy<secp256k1::PublicKey>>>(iter: I) -> Self {
+        let mut set = LegacyKeySet::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<'a> IntoIterator for &'a LegacyKeySet {
+    type Item = Legacy<secp256k1::PublicKey>;
+    type IntoIter = Iter<'a>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter { self.iter() }
+}
+
+/// Iterator over the keys in [`LegacyKeySet`].
+#[derive(Debug, Clone)]
+pub struct Iter<'a>(btree_map::Iter<'a, secp256k1::PublicKey, KeyFormat>);
+
+impl Iterator for Iter<'_> {
+    type Item = Legacy<secp256k1::PublicKey>;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0
+            .next()
+            .map(|(key, format)| Legacy::from_raw(*key, *format))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) { self.0.size_hint() }
+}
+
+impl ExactSizeIterator for Iter<'_> {}
+
+#[cfg(test)]
+mod test {
+    use secp256k1::{Secp256k1, SecretKey};
+
+    use super::*;
+
+    #[test]
+    fn dedup_ignores_format() {
+        let secret = SecretKey::from_slice(&[42; 32]).unwrap();
+        let key = secret.public_key(&Secp256k1::signing_only());
+
+        let mut set = LegacyKeySet::new();
+        assert!(set.insert(Legacy::from_raw(key, KeyFormat::Uncompressed)));
+        assert!(!set.insert(Legacy::from_raw(key, KeyFormat::Compressed)));
+        assert_eq!(set.len(), 1);
+        assert_eq!(
+            set.get(&key),
+            Some(Legacy::from_raw(key, KeyFormat::Uncompressed))
+        );
+
+        let other = SecretKey::from_slice(&[43; 32])
+            .unwrap()
+            .public_key(&Secp256k1::signing_only());
+        set.extend([Legacy::from_raw(other, KeyFormat::Compressed)]);
+        assert_eq!(set.iter().len(), 2);
+        assert!(set.contains(&other));
+        assert_eq!(
+            set.remove(&key),
+            Some(Legacy::from_raw(key, KeyFormat::Uncompressed))
+        );
+        assert!(!set.contains(&key));
+    }
+}
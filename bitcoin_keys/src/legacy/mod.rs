@@ -20,14 +20,24 @@
 //! use their generic properties and use type aliases in the crate root instead.
 //! Them being generic is mainly avoiding code duplication in this crate.
 
+#[cfg(feature = "audit")]
+#[cfg_attr(docsrs, doc(cfg(feature = "audit")))]
+pub mod audit;
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+pub mod key_set;
 pub mod serialized_public_key;
 
 use core::convert::TryFrom;
 use core::fmt;
 
+#[cfg(feature = "alloc")]
+pub use key_set::LegacyKeySet;
 use secp256k1::Secp256k1;
 pub use serialized_public_key::SerializedPublicKey;
 
+use crate::hex::HexError;
+
 /// Distinguishes compressed keys from uncompressed ones (runtime).
 ///
 /// This is a more readable alternative to `bool`.
@@ -55,6 +65,54 @@ impl KeyFormat {
     /// [`Uncompressed`]: Self::Uncompressed
     #[inline]
     pub fn is_uncompressed(self) -> bool { self == KeyFormat::Uncompressed }
+
+    /// Returns the canonical name of the format.
+    ///
+    /// This is `"compressed"` or `"uncompressed"`, the same string the
+    /// `Display` implementation writes.
+    #[inline]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            KeyFormat::Compressed => "compressed",
+            KeyFormat::Uncompressed => "uncompressed",
+        }
+    }
+
+    /// Guesses the format of the key from the prefix of the address it was
+    /// used in.
+    ///
+    /// SegWit v0 addresses (bech32 with `bc1q`, `tb1q` or `bcrt1q` prefix, in
+    /// any case) can only use compressed keys so this returns [`Compressed`]
+    /// for them. Base58 addresses (`1`, `3`, `m`, `n`, `2`) may use either
+    /// format so `None` is returned and the caller has to try both. `None` is
+    /// also returned for other witness versions such as Taproot (`bc1p`) since
+    /// those use x-only keys which have no format.
+    ///
+    /// This is a heuristic intended for recovery tools, it doesn't validate
+    /// the address.
+    ///
+    /// [`Compressed`]: Self::Compressed
+    pub fn infer_from_address_prefix(address: &str) -> Option<Self> {
+        const SEGWIT_V0_PREFIXES: [&str; 3] = ["bc1q", "tb1q", "bcrt1q"];
+
+        let is_segwit_v0 =
+            SEGWIT_V0_PREFIXES
+                .iter()
+                .any(|prefix| match address.get(..prefix.len()) {
+                    Some(start) => start.eq_ignore_ascii_case(prefix),
+                    None => false,
+                });
+        if is_segwit_v0 {
+            Some(KeyFormat::Compressed)
+        } else {
+            None
+        }
+    }
+}
+
+impl fmt::Display for KeyFormat {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result { f.write_str(self.as_str()) }
 }
 
 /// Turns compressed format to uncompressed and vice versa.
@@ -70,14 +128,43 @@ impl core::ops::Not for KeyFormat {
     }
 }
 
+/// Returns the SEC1 prefix of a compressed public key with given y-parity.
+///
+/// The prefix is `0x02` for even and `0x03` for odd y-coordinate.
+#[inline]
+pub fn sec1_compressed_prefix(parity: secp256k1::Parity) -> u8 {
+    match parity {
+        secp256k1::Parity::Even => 0x02,
+        secp256k1::Parity::Odd => 0x03,
+    }
+}
+
 mod sealed {
     use secp256k1::Secp256k1;
 
-    pub trait Key: Copy + Eq {}
+    pub trait Key: Copy + Eq {
+        /// Leading bytes of the x-coordinate of the public key used to
+        /// identify the key in errors, `None` if the public key is not
+        /// available.
+        fn public_key_prefix(&self) -> Option<[u8; 8]>;
+    }
+
+    impl Key for secp256k1::PublicKey {
+        fn public_key_prefix(&self) -> Option<[u8; 8]> {
+            let mut prefix = [0u8; 8];
+            // skip the SEC1 prefix byte, it's the same for all uncompressed keys
+            prefix.copy_from_slice(&self.serialize_uncompressed()[1..9]);
+            Some(prefix)
+        }
+    }
+
+    impl Key for secp256k1::SecretKey {
+        fn public_key_prefix(&self) -> Option<[u8; 8]> { None }
+    }
 
-    impl Key for secp256k1::PublicKey {}
-    impl Key for secp256k1::SecretKey {}
-    impl Key for secp256k1::KeyPair {}
+    impl Key for crate::SecpKeyPair {
+        fn public_key_prefix(&self) -> Option<[u8; 8]> { self.public_key().public_key_prefix() }
+    }
 
     pub trait PublicKey: Key {
         fn public_key(self) -> secp256k1::PublicKey;
@@ -88,11 +175,16 @@ mod sealed {
         fn public_key(self) -> secp256k1::PublicKey { self }
     }
 
-    impl PublicKey for secp256k1::KeyPair {
+    impl PublicKey for crate::SecpKeyPair {
         #[inline]
         fn public_key(self) -> secp256k1::PublicKey { self.into() }
     }
 
+    pub trait KeyWrapper {}
+
+    impl<K: super::Key> KeyWrapper for super::Legacy<K> {}
+    impl<K: super::Key> KeyWrapper for super::Compressed<K> {}
+
     pub trait PrivateKey: Key {
         fn private_key(self) -> secp256k1::SecretKey;
 
@@ -110,7 +202,7 @@ mod sealed {
         fn private_key(self) -> secp256k1::SecretKey { self }
     }
 
-    impl PrivateKey for secp256k1::KeyPair {
+    impl PrivateKey for crate::SecpKeyPair {
         #[inline]
         fn private_key(self) -> secp256k1::SecretKey { self.into() }
 
@@ -134,14 +226,39 @@ pub trait PublicKey: Key + sealed::PublicKey {}
 /// Represents key types that are or contain private keys.
 pub trait PrivateKey: Key + sealed::PrivateKey {}
 
+/// Common interface of the [`Legacy`] and [`Compressed`] wrappers.
+///
+/// This allows writing code generic over both wrappers.
+pub trait KeyWrapper: sealed::KeyWrapper {
+    /// The wrapped key.
+    type Key: Key;
+
+    /// Returns true if the wrappers contain the same key, ignoring the format.
+    fn same_point(&self, other: &Self) -> bool;
+}
+
+impl<K: Key> KeyWrapper for Legacy<K> {
+    type Key = K;
+
+    #[inline]
+    fn same_point(&self, other: &Self) -> bool { Legacy::same_point(self, other) }
+}
+
+impl<K: Key> KeyWrapper for Compressed<K> {
+    type Key = K;
+
+    #[inline]
+    fn same_point(&self, other: &Self) -> bool { Compressed::same_point(self, other) }
+}
+
 impl Key for secp256k1::PublicKey {}
 impl Key for secp256k1::SecretKey {}
-impl Key for secp256k1::KeyPair {}
+impl Key for crate::SecpKeyPair {}
 
 impl PublicKey for secp256k1::PublicKey {}
 impl PrivateKey for secp256k1::SecretKey {}
-impl PublicKey for secp256k1::KeyPair {}
-impl PrivateKey for secp256k1::KeyPair {}
+impl PublicKey for crate::SecpKeyPair {}
+impl PrivateKey for crate::SecpKeyPair {}
 
 /// Contains a key that may be uncompressed when serialized as public key.
 ///
@@ -172,6 +289,20 @@ impl<K: Key> Legacy<K> {
     #[inline]
     pub fn raw_key(self) -> K { self.key }
 
+    /// Borrows the underlying secp256k1 key.
+    ///
+    /// Note that this exposes the secret if `K` is a private key or a key pair.
+    #[inline]
+    pub fn as_inner(&self) -> &K { &self.key }
+
+    /// Returns the underlying secp256k1 key, discarding the format.
+    ///
+    /// This is the same as [`raw_key`](Self::raw_key), provided for
+    /// consistency with other wrappers. Note that this exposes the secret if
+    /// `K` is a private key or a key pair.
+    #[inline]
+    pub fn into_inner(self) -> K { self.key }
+
     /// Dangerous: Overrides the format.
     ///
     /// This method may change the format and result in a different address.
@@ -181,7 +312,11 @@ impl<K: Key> Legacy<K> {
     /// The method should only be used when this behavior is known to be
     /// correct, e.g. in recovery tools.
     #[inline]
-    pub fn force_set_format(&mut self, format: KeyFormat) { self.format = format; }
+    pub fn force_set_format(&mut self, format: KeyFormat) {
+        #[cfg(feature = "audit")]
+        audit::notify(&self.format, &format);
+        self.format = format;
+    }
 
     /// Dangerous: Forces the format to be compressed.
     ///
@@ -192,7 +327,11 @@ impl<K: Key> Legacy<K> {
     /// The method should only be used when this behavior is known to be
     /// correct, e.g. in recovery tools.
     #[inline]
-    pub fn force_to_compressed(self) -> Compressed<K> { Compressed::from_raw(self.key) }
+    pub fn force_to_compressed(self) -> Compressed<K> {
+        #[cfg(feature = "audit")]
+        audit::notify(&self.format, &KeyFormat::Compressed);
+        Compressed::from_raw(self.key)
+    }
 
     /// Returns true if the keys are equal *regardless of the format*.
     ///
@@ -201,6 +340,13 @@ impl<K: Key> Legacy<K> {
     /// ignores the format when comparing.
     #[inline]
     pub fn eq_key(self, rhs: Self) -> bool { self.key == rhs.key }
+
+    /// Returns true if the keys are equal *regardless of the format*.
+    ///
+    /// This is the same as [`eq_key`](Self::eq_key) but takes references,
+    /// consistently with [`Compressed::same_point`].
+    #[inline]
+    pub fn same_point(&self, other: &Self) -> bool { self.key == other.key }
 }
 
 impl<K: PublicKey> Legacy<K> {
@@ -218,6 +364,69 @@ impl<K: PublicKey> Legacy<K> {
     pub fn serialize_public_key(self) -> SerializedPublicKey {
         SerializedPublicKey::new(self.key.public_key(), self.format)
     }
+
+    /// Returns an iterator over the serialized bytes of the public key.
+    ///
+    /// The iterator owns the serialized data so it can be passed around or
+    /// collected into any container. It yields 33 or 65 bytes depending on
+    /// the format.
+    #[inline]
+    pub fn public_key_bytes(&self) -> serialized_public_key::IntoIter {
+        self.serialize_public_key().into_iter()
+    }
+
+    /// Serializes the public key in the given format, ignoring the stored one.
+    ///
+    /// This is intended for inspection and recovery, e.g. to find out which
+    /// serialization of a key was used in an address. As opposed to
+    /// [`force_set_format`](Self::force_set_format) it doesn't modify the key.
+    #[inline]
+    pub fn reserialize_as(&self, format: KeyFormat) -> SerializedPublicKey {
+        SerializedPublicKey::new(self.key.public_key(), format)
+    }
+
+    /// Computes HASH160 of the serialized public key.
+    ///
+    /// The hash depends on the format so compressed and uncompressed
+    /// serializations of the same key produce different hashes and thus
+    /// different addresses.
+    #[cfg(feature = "bitcoin_hashes")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "bitcoin_hashes")))]
+    pub fn pubkey_hash(&self) -> [u8; 20] {
+        use bitcoin_hashes::Hash;
+
+        self.pubkey_hash160().into_inner()
+    }
+
+    /// Computes HASH160 of the serialized public key as a `bitcoin_hashes`
+    /// type.
+    ///
+    /// This is the same as [`pubkey_hash`](Self::pubkey_hash) but the result
+    /// interoperates with the `bitcoin_hashes` ecosystem.
+    #[cfg(feature = "bitcoin_hashes")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "bitcoin_hashes")))]
+    pub fn pubkey_hash160(&self) -> bitcoin_hashes::hash160::Hash {
+        use bitcoin_hashes::{hash160, Hash};
+
+        hash160::Hash::hash(&self.serialize_public_key())
+    }
+
+    /// Returns the P2PKH script pubkey paying to this key.
+    ///
+    /// The script is `OP_DUP OP_HASH160 <pubkey_hash> OP_EQUALVERIFY
+    /// OP_CHECKSIG` where the hash is computed by
+    /// [`pubkey_hash`](Self::pubkey_hash), so it respects the format.
+    #[cfg(feature = "bitcoin_hashes")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "bitcoin_hashes")))]
+    pub fn p2pkh_script(&self) -> [u8; 25] {
+        let mut script = [0u8; 25];
+        // OP_DUP OP_HASH160 OP_PUSHBYTES_20
+        script[..3].copy_from_slice(&[0x76, 0xa9, 0x14]);
+        script[3..23].copy_from_slice(&self.pubkey_hash());
+        // OP_EQUALVERIFY OP_CHECKSIG
+        script[23..].copy_from_slice(&[0x88, 0xac]);
+        script
+    }
 }
 
 impl<K: PrivateKey> Legacy<K> {
@@ -248,6 +457,27 @@ impl<K: Key> Compressed<K> {
 
     /// Returns the raw key.
     pub fn raw_key(self) -> K { self.key }
+
+    /// Borrows the underlying secp256k1 key.
+    ///
+    /// Note that this exposes the secret if `K` is a private key or a key pair.
+    #[inline]
+    pub fn as_inner(&self) -> &K { &self.key }
+
+    /// Returns the underlying secp256k1 key.
+    ///
+    /// This is the same as [`raw_key`](Self::raw_key), provided for
+    /// consistency with other wrappers. Note that this exposes the secret if
+    /// `K` is a private key or a key pair.
+    #[inline]
+    pub fn into_inner(self) -> K { self.key }
+
+    /// Returns true if the keys are equal.
+    ///
+    /// This is the same as `==` since compressed keys carry no format. It's
+    /// provided for symmetry with [`Legacy::same_point`].
+    #[inline]
+    pub fn same_point(&self, other: &Self) -> bool { self.key == other.key }
 }
 
 impl<K: PublicKey> Compressed<K> {
@@ -258,6 +488,86 @@ impl<K: PublicKey> Compressed<K> {
     /// Bitcoin script.
     #[inline]
     pub fn serialize_public_key(self) -> [u8; 33] { self.key.public_key().serialize() }
+
+    /// Serializes the public key returning the prefix and the x-coordinate
+    /// separately.
+    ///
+    /// The prefix is `0x02` if the y-coordinate is even and `0x03` if it's odd.
+    /// This is useful for compact encodings storing the parity elsewhere.
+    #[inline]
+    pub fn serialize_split(self) -> (u8, [u8; 32]) {
+        let serialized = self.serialize_public_key();
+        let mut x = [0u8; 32];
+        x.copy_from_slice(&serialized[1..]);
+        (serialized[0], x)
+    }
+
+    /// Returns `true` if the x-coordinate of this key equals the x-only key.
+    ///
+    /// The parity of the y-coordinate is ignored so both points sharing the
+    /// x-coordinate match. This is useful when correlating a key across ECDSA
+    /// and Taproot contexts.
+    #[inline]
+    pub fn matches_xonly(self, xonly: &secp256k1::XOnlyPublicKey) -> bool {
+        self.key.public_key().x_only_public_key().0 == *xonly
+    }
+
+    /// Computes HASH160 of the compressed public key.
+    #[cfg(feature = "bitcoin_hashes")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "bitcoin_hashes")))]
+    pub fn pubkey_hash(&self) -> [u8; 20] {
+        use bitcoin_hashes::Hash;
+
+        self.pubkey_hash160().into_inner()
+    }
+
+    /// Computes HASH160 of the serialized public key as a `bitcoin_hashes`
+    /// type.
+    ///
+    /// This is the same as [`pubkey_hash`](Self::pubkey_hash) but the result
+    /// interoperates with the `bitcoin_hashes` ecosystem.
+    #[cfg(feature = "bitcoin_hashes")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "bitcoin_hashes")))]
+    pub fn pubkey_hash160(&self) -> bitcoin_hashes::hash160::Hash {
+        use bitcoin_hashes::{hash160, Hash};
+
+        hash160::Hash::hash(&self.serialize_public_key())
+    }
+
+    /// Returns the P2WPKH witness program used as the redeem script of nested
+    /// SegWit (P2SH-P2WPKH) outputs.
+    ///
+    /// The script is `OP_0 <pubkey_hash>`.
+    #[cfg(feature = "bitcoin_hashes")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "bitcoin_hashes")))]
+    pub fn p2sh_p2wpkh_redeem_script(&self) -> [u8; 22] {
+        let mut script = [0u8; 22];
+        // OP_0 OP_PUSHBYTES_20
+        script[..2].copy_from_slice(&[0x00, 0x14]);
+        script[2..].copy_from_slice(&self.pubkey_hash());
+        script
+    }
+
+    /// Returns the P2SH script pubkey of the nested SegWit (P2SH-P2WPKH)
+    /// output paying to this key.
+    ///
+    /// The script is `OP_HASH160 <redeem_script_hash> OP_EQUAL` where the
+    /// redeem script is
+    /// [`p2sh_p2wpkh_redeem_script`](Self::p2sh_p2wpkh_redeem_script).
+    #[cfg(feature = "bitcoin_hashes")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "bitcoin_hashes")))]
+    pub fn p2sh_p2wpkh_script_pubkey(&self) -> [u8; 23] {
+        use bitcoin_hashes::{hash160, Hash};
+
+        let script_hash = hash160::Hash::hash(&self.p2sh_p2wpkh_redeem_script());
+        let mut script = [0u8; 23];
+        // OP_HASH160 OP_PUSHBYTES_20
+        script[..2].copy_from_slice(&[0xa9, 0x14]);
+        script[2..22].copy_from_slice(&script_hash);
+        // OP_EQUAL
+        script[22] = 0x87;
+        script
+    }
 }
 
 impl<K: PrivateKey> Compressed<K> {
@@ -268,28 +578,152 @@ impl<K: PrivateKey> Compressed<K> {
     ) -> Compressed<secp256k1::PublicKey> {
         Compressed::from_raw(self.key.compute_public_key(context))
     }
+
+    /// Computes a public key from this private key without requiring a
+    /// context.
+    ///
+    /// This creates a signing context in a small stack buffer relying on the
+    /// static precomputed tables of libsecp256k1 so it works on embedded
+    /// targets without an allocator or a source of randomness.
+    ///
+    /// **Warning:** the temporary context is not randomized which removes the
+    /// blinding protecting the secret against side-channel attacks such as
+    /// power analysis. Prefer [`compute_public_key`](Self::compute_public_key)
+    /// with a randomized context if the device may be physically accessible
+    /// to an attacker.
+    pub fn public_key_no_ctx(self) -> Compressed<secp256k1::PublicKey> {
+        crate::stack_context::with_signing_context(|context| self.compute_public_key(context))
+    }
+}
+
+impl Legacy<crate::SecpKeyPair> {
+    /// Returns the public key, preserving the format.
+    ///
+    /// As opposed to [`compute_public_key`](Self::compute_public_key) this
+    /// doesn't need a context because the key pair already contains the
+    /// public key.
+    #[inline]
+    pub fn public_key_wrapper(&self) -> Legacy<secp256k1::PublicKey> { (*self).into() }
+
+    /// Splits the key pair into public and private key, preserving the format.
+    #[inline]
+    pub fn split(self) -> (Legacy<secp256k1::PublicKey>, Legacy<secp256k1::SecretKey>) {
+        (self.into(), self.into())
+    }
+
+    /// Signs the message using ECDSA.
+    ///
+    /// The signature is low-S as required by Bitcoin. The format of the key
+    /// doesn't affect the signature, only the public key it's verified
+    /// against.
+    #[inline]
+    pub fn sign_ecdsa<C: secp256k1::Signing>(
+        &self,
+        context: &Secp256k1<C>,
+        message: &secp256k1::Message,
+    ) -> secp256k1::ecdsa::Signature {
+        context.sign_ecdsa(message, &self.key.secret_key())
+    }
+}
+
+impl Compressed<crate::SecpKeyPair> {
+    /// Returns the public key.
+    ///
+    /// As opposed to [`compute_public_key`](Self::compute_public_key) this
+    /// doesn't need a context because the key pair already contains the
+    /// public key.
+    #[inline]
+    pub fn public_key_wrapper(&self) -> Compressed<secp256k1::PublicKey> { (*self).into() }
+
+    /// Splits the key pair into public and private key.
+    #[inline]
+    pub fn split(
+        self,
+    ) -> (
+        Compressed<secp256k1::PublicKey>,
+        Compressed<secp256k1::SecretKey>,
+    ) {
+        (self.into(), self.into())
+    }
+
+    /// Signs the message using ECDSA.
+    ///
+    /// The signature is low-S as required by Bitcoin. There's intentionally
+    /// no method producing schnorr signatures, convert the key pair using
+    /// [`into_x_only`](Self::into_x_only) first so that keys used in
+    /// different domains can't be mixed up accidentally.
+    #[inline]
+    pub fn sign_ecdsa<C: secp256k1::Signing>(
+        &self,
+        context: &Secp256k1<C>,
+        message: &secp256k1::Message,
+    ) -> secp256k1::ecdsa::Signature {
+        context.sign_ecdsa(message, &self.key.secret_key())
+    }
+
+    /// Converts the key pair into one intended for schnorr signatures.
+    ///
+    /// **Warning:** using the same key for both ECDSA and schnorr signatures
+    /// is discouraged. This is intended for keys that are switching domains,
+    /// not for signing with both.
+    #[inline]
+    pub fn into_x_only(self) -> crate::XOnlyKeyPair { crate::XOnlyKeyPair::from_raw(self.key) }
+}
+
+impl AsRef<secp256k1::PublicKey> for Legacy<secp256k1::PublicKey> {
+    #[inline]
+    fn as_ref(&self) -> &secp256k1::PublicKey { &self.key }
+}
+
+impl AsRef<secp256k1::PublicKey> for Compressed<secp256k1::PublicKey> {
+    #[inline]
+    fn as_ref(&self) -> &secp256k1::PublicKey { &self.key }
+}
+
+impl From<&Legacy<secp256k1::PublicKey>> for SerializedPublicKey {
+    #[inline]
+    fn from(value: &Legacy<secp256k1::PublicKey>) -> Self { value.serialize_public_key() }
 }
 
-impl From<Legacy<secp256k1::KeyPair>> for Legacy<secp256k1::PublicKey> {
-    fn from(value: Legacy<secp256k1::KeyPair>) -> Self {
+/// The key is serialized as compressed, producing 33 bytes.
+impl From<&Compressed<secp256k1::PublicKey>> for SerializedPublicKey {
+    #[inline]
+    fn from(value: &Compressed<secp256k1::PublicKey>) -> Self {
+        SerializedPublicKey::new(value.key, KeyFormat::Compressed)
+    }
+}
+
+/// Drops the parity of the y-coordinate.
+///
+/// **Warning:** this conversion is lossy. The resulting x-only key represents
+/// the point with even y-coordinate which is the negation of the original
+/// point if its y-coordinate is odd. Use
+/// [`serialize_split`](Compressed::serialize_split) if the parity is needed.
+impl From<Compressed<secp256k1::PublicKey>> for secp256k1::XOnlyPublicKey {
+    #[inline]
+    fn from(value: Compressed<secp256k1::PublicKey>) -> Self { value.key.x_only_public_key().0 }
+}
+
+impl From<Legacy<crate::SecpKeyPair>> for Legacy<secp256k1::PublicKey> {
+    fn from(value: Legacy<crate::SecpKeyPair>) -> Self {
         Legacy::from_raw(value.raw_key().into(), value.format())
     }
 }
 
-impl From<Legacy<secp256k1::KeyPair>> for Legacy<secp256k1::SecretKey> {
-    fn from(value: Legacy<secp256k1::KeyPair>) -> Self {
+impl From<Legacy<crate::SecpKeyPair>> for Legacy<secp256k1::SecretKey> {
+    fn from(value: Legacy<crate::SecpKeyPair>) -> Self {
         Legacy::from_raw(value.raw_key().into(), value.format())
     }
 }
 
-impl From<Compressed<secp256k1::KeyPair>> for Compressed<secp256k1::PublicKey> {
-    fn from(value: Compressed<secp256k1::KeyPair>) -> Self {
+impl From<Compressed<crate::SecpKeyPair>> for Compressed<secp256k1::PublicKey> {
+    fn from(value: Compressed<crate::SecpKeyPair>) -> Self {
         Compressed::from_raw(value.raw_key().into())
     }
 }
 
-impl From<Compressed<secp256k1::KeyPair>> for Compressed<secp256k1::SecretKey> {
-    fn from(value: Compressed<secp256k1::KeyPair>) -> Self {
+impl From<Compressed<crate::SecpKeyPair>> for Compressed<secp256k1::SecretKey> {
+    fn from(value: Compressed<crate::SecpKeyPair>) -> Self {
         Compressed::from_raw(value.raw_key().into())
     }
 }
@@ -304,7 +738,357 @@ impl<K: Key> TryFrom<Legacy<K>> for Compressed<K> {
     fn try_from(value: Legacy<K>) -> Result<Self, Self::Error> {
         match value.format() {
             KeyFormat::Compressed => Ok(Self::from_raw(value.raw_key())),
-            KeyFormat::Uncompressed => Err(KeyNotCompressedError {}),
+            KeyFormat::Uncompressed => Err(KeyNotCompressedError {
+                key_prefix: sealed::Key::public_key_prefix(&value.key),
+            }),
+        }
+    }
+}
+
+impl Legacy<secp256k1::PublicKey> {
+    /// Parses the public key from SEC1 encoding, detecting the format from the
+    /// length.
+    ///
+    /// 33 bytes are parsed as compressed and 65 bytes as uncompressed key. The
+    /// bytes are validated to represent a point on the curve. Hybrid keys
+    /// (prefix `0x06` or `0x07`) are rejected.
+    pub fn from_sec1_bytes(bytes: &[u8]) -> Result<Self, PublicKeyParseError> {
+        let format = match bytes.len() {
+            33 => KeyFormat::Compressed,
+            // secp256k1 also accepts hybrid keys which would serialize differently so we have
+            // to reject them here.
+            65 if bytes[0] == 0x04 => KeyFormat::Uncompressed,
+            65 => {
+                return Err(PublicKeyParseError::InvalidKey(
+                    secp256k1::Error::InvalidPublicKey,
+                ))
+            }
+            len => return Err(PublicKeyParseError::InvalidLength(len)),
+        };
+        secp256k1::PublicKey::from_slice(bytes)
+            .map(|key| Legacy::from_raw(key, format))
+            .map_err(PublicKeyParseError::InvalidKey)
+    }
+}
+
+impl Compressed<secp256k1::PublicKey> {
+    /// Parses the public key from compressed SEC1 encoding.
+    ///
+    /// The key must be 33 bytes long and represent a point on the curve.
+    pub fn from_sec1_bytes(bytes: &[u8]) -> Result<Self, PublicKeyParseError> {
+        if bytes.len() != 33 {
+            return Err(PublicKeyParseError::InvalidLength(bytes.len()));
+        }
+        secp256k1::PublicKey::from_slice(bytes)
+            .map(Compressed::from_raw)
+            .map_err(PublicKeyParseError::InvalidKey)
+    }
+
+    /// Tweaks the key by adding `tweak * G` to it.
+    ///
+    /// Returns an error if the result is the point at infinity.
+    pub fn add_tweak<C: secp256k1::Verification>(
+        self,
+        context: &Secp256k1<C>,
+        tweak: &crate::Scalar,
+    ) -> Result<Self, secp256k1::Error> {
+        self.key
+            .add_exp_tweak(context, tweak)
+            .map(Compressed::from_raw)
+    }
+
+    /// Tweaks the key like [`add_tweak`](Self::add_tweak), also returning
+    /// the parity of the y-coordinate of the result.
+    ///
+    /// The parity is needed when the tweaked key is later used as an x-only
+    /// key, e.g. in pay-to-contract schemes.
+    pub fn add_tweak_with_parity<C: secp256k1::Verification>(
+        self,
+        context: &Secp256k1<C>,
+        tweak: &crate::Scalar,
+    ) -> Result<(Self, secp256k1::Parity), secp256k1::Error> {
+        let key = self.add_tweak(context, tweak)?;
+        let (_, parity) = key.key.x_only_public_key();
+        Ok((key, parity))
+    }
+
+    /// Derives a child key by adding `tweak * G`.
+    ///
+    /// This is the public counterpart of
+    /// [`Compressed::<SecretKey>::derive_child_add`](Compressed::derive_child_add)
+    /// so the child public key can be derived without knowing the secret. It's
+    /// the same operation as [`add_tweak`](Self::add_tweak).
+    #[inline]
+    pub fn derive_child_add<C: secp256k1::Verification>(
+        self,
+        context: &Secp256k1<C>,
+        tweak: &crate::Scalar,
+    ) -> Result<Self, secp256k1::Error> {
+        self.add_tweak(context, tweak)
+    }
+
+    /// Blinds the key by adding `blinding * G` to it.
+    ///
+    /// This is the same operation as tweak-add but named for use in
+    /// confidential protocols. Use [`unblind`](Self::unblind) with the same
+    /// factor to get the original key back.
+    ///
+    /// Returns an error if the result is the point at infinity.
+    pub fn blind<C: secp256k1::Verification>(
+        self,
+        context: &Secp256k1<C>,
+        blinding: &crate::Scalar,
+    ) -> Result<Self, secp256k1::Error> {
+        self.add_tweak(context, blinding)
+    }
+
+    /// Removes the blinding by subtracting `blinding * G` from the key.
+    ///
+    /// This is the inverse of [`blind`](Self::blind).
+    ///
+    /// Returns an error if the result is the point at infinity.
+    pub fn unblind<C: secp256k1::Verification>(
+        self,
+        context: &Secp256k1<C>,
+        blinding: &crate::Scalar,
+    ) -> Result<Self, secp256k1::Error> {
+        // P - bG = -(-P + bG)
+        self.key
+            .negate(context)
+            .add_exp_tweak(context, blinding)
+            .map(|key| Compressed::from_raw(key.negate(context)))
+    }
+}
+
+impl Legacy<secp256k1::SecretKey> {
+    /// Parses the secret key from 32 bytes, attaching the format.
+    pub fn from_secret_slice(bytes: &[u8], format: KeyFormat) -> Result<Self, SecretKeyParseError> {
+        parse_secret_key(bytes).map(|key| Legacy::from_raw(key, format))
+    }
+}
+
+impl Compressed<secp256k1::SecretKey> {
+    /// Derives a child key by adding the tweak to the secret.
+    ///
+    /// This is a simple additive derivation, not BIP-32. The public key of
+    /// the child equals the result of
+    /// [`Compressed::<PublicKey>::derive_child_add`](Compressed::derive_child_add)
+    /// applied to the parent public key with the same tweak.
+    ///
+    /// Returns an error if the resulting secret is zero.
+    #[inline]
+    pub fn derive_child_add(self, tweak: &crate::Scalar) -> Result<Self, secp256k1::Error> {
+        self.key.add_tweak(tweak).map(Compressed::from_raw)
+    }
+
+    /// Iterates over `count` consecutive secrets beginning at `start`,
+    /// yielding each secret with the HASH160 of its compressed public key.
+    ///
+    /// This is intended for wallet recovery scanning for P2WPKH and
+    /// P2SH-P2WPKH outputs. Nothing is allocated.
+    ///
+    /// Zero is not a valid secret so it's skipped (but counted) if `start` is
+    /// zero. The iteration stops early if it reaches the curve order.
+    #[cfg(feature = "bitcoin_hashes")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "bitcoin_hashes")))]
+    pub fn scan_range<'a, C: secp256k1::Signing>(
+        context: &'a Secp256k1<C>,
+        start: &crate::Scalar,
+        count: u64,
+    ) -> ScanRange<'a, C> {
+        let (next, remaining) = match secp256k1::SecretKey::from_slice(&start.to_be_bytes()) {
+            Ok(key) => (Some(key), count),
+            Err(_) => (
+                secp256k1::SecretKey::from_slice(&crate::Scalar::ONE.to_be_bytes()).ok(),
+                count.saturating_sub(1),
+            ),
+        };
+        ScanRange {
+            context,
+            next,
+            remaining,
+        }
+    }
+}
+
+/// Iterator returned by [`Compressed::scan_range`].
+#[cfg(feature = "bitcoin_hashes")]
+#[cfg_attr(docsrs, doc(cfg(feature = "bitcoin_hashes")))]
+#[derive(Debug, Clone)]
+pub struct ScanRange<'a, C: secp256k1::Signing> {
+    context: &'a Secp256k1<C>,
+    next: Option<secp256k1::SecretKey>,
+    remaining: u64,
+}
+
+#[cfg(feature = "bitcoin_hashes")]
+impl<C: secp256k1::Signing> Iterator for ScanRange<'_, C> {
+    type Item = (crate::Scalar, [u8; 20]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let key = self.next?;
+        self.remaining -= 1;
+        // fails only if the secret wrapped around to zero
+        self.next = key.add_tweak(&crate::Scalar::ONE).ok();
+        let hash = Compressed::from_raw(key.public_key(self.context)).pubkey_hash();
+        Some((key.into(), hash))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        if self.next.is_none() {
+            return (0, Some(0));
+        }
+        // may end early when reaching the curve order
+        (0, usize::try_from(self.remaining).ok())
+    }
+}
+
+#[cfg(feature = "bitcoin_hashes")]
+impl<C: secp256k1::Signing> core::iter::FusedIterator for ScanRange<'_, C> {}
+
+/// Parses the secret key from 32 bytes.
+impl TryFrom<&[u8]> for Compressed<secp256k1::SecretKey> {
+    type Error = SecretKeyParseError;
+
+    #[inline]
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
+        parse_secret_key(value).map(Compressed::from_raw)
+    }
+}
+
+fn parse_secret_key(bytes: &[u8]) -> Result<secp256k1::SecretKey, SecretKeyParseError> {
+    if bytes.len() != 32 {
+        return Err(SecretKeyParseError::InvalidLength(bytes.len()));
+    }
+    secp256k1::SecretKey::from_slice(bytes).map_err(SecretKeyParseError::InvalidKey)
+}
+
+/// Same as [`Legacy::from_sec1_bytes`].
+impl TryFrom<&[u8]> for Legacy<secp256k1::PublicKey> {
+    type Error = PublicKeyParseError;
+
+    #[inline]
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> { Legacy::from_sec1_bytes(value) }
+}
+
+/// Same as [`Compressed::from_sec1_bytes`].
+impl TryFrom<&[u8]> for Compressed<secp256k1::PublicKey> {
+    type Error = PublicKeyParseError;
+
+    #[inline]
+    fn try_from(value: &[u8]) -> Result<Self, Self::Error> { Compressed::from_sec1_bytes(value) }
+}
+
+/// Parses the public key from hex, detecting the format from the length.
+///
+/// 33 bytes are parsed as compressed and 65 bytes as uncompressed key. The
+/// input may optionally be prefixed with `0x` or `0X`.
+impl core::str::FromStr for Legacy<secp256k1::PublicKey> {
+    type Err = PublicKeyParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut buf = [0u8; 65];
+        Legacy::from_sec1_bytes(decode_public_key_hex(s, &mut buf)?)
+    }
+}
+
+/// Parses the public key from hex.
+///
+/// The key must be 33 bytes long. The input may optionally be prefixed with
+/// `0x` or `0X`.
+impl core::str::FromStr for Compressed<secp256k1::PublicKey> {
+    type Err = PublicKeyParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut buf = [0u8; 65];
+        Compressed::from_sec1_bytes(decode_public_key_hex(s, &mut buf)?)
+    }
+}
+
+/// Decodes hex string into the buffer, returning the decoded part.
+pub(crate) fn decode_public_key_hex<'a>(
+    s: &str,
+    buf: &'a mut [u8; 65],
+) -> Result<&'a [u8], PublicKeyParseError> {
+    let s = crate::hex::strip_prefix(s);
+    let len = crate::hex::decoded_len(s).map_err(PublicKeyParseError::InvalidHex)?;
+    let buf = buf
+        .get_mut(..len)
+        .ok_or(PublicKeyParseError::InvalidLength(len))?;
+    crate::hex::decode_to_slice(s, buf).map_err(PublicKeyParseError::InvalidHex)?;
+    Ok(buf)
+}
+
+/// Returned when parsing a public key from string fails.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum PublicKeyParseError {
+    /// The string is not valid hex.
+    InvalidHex(HexError),
+    /// The decoded key has invalid length.
+    InvalidLength(usize),
+    /// The decoded bytes don't represent a valid key.
+    InvalidKey(secp256k1::Error),
+}
+
+impl fmt::Display for PublicKeyParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PublicKeyParseError::InvalidHex(_) => f.write_str("invalid hex encoding of public key"),
+            PublicKeyParseError::InvalidLength(len) => {
+                write!(f, "invalid public key length {}", len)
+            }
+            PublicKeyParseError::InvalidKey(_) => f.write_str("invalid public key"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+impl std::error::Error for PublicKeyParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PublicKeyParseError::InvalidHex(error) => Some(error),
+            PublicKeyParseError::InvalidLength(_) => None,
+            PublicKeyParseError::InvalidKey(error) => Some(error),
+        }
+    }
+}
+
+/// Returned when parsing a secret key from bytes fails.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum SecretKeyParseError {
+    /// The input is not 32 bytes long.
+    InvalidLength(usize),
+    /// The input is zero or not below the curve order.
+    InvalidKey(secp256k1::Error),
+}
+
+impl fmt::Display for SecretKeyParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SecretKeyParseError::InvalidLength(len) => write!(
+                f,
+                "invalid secret key length {}, the length must be 32 bytes",
+                len
+            ),
+            SecretKeyParseError::InvalidKey(_) => f.write_str("invalid secret key"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+impl std::error::Error for SecretKeyParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            SecretKeyParseError::InvalidLength(_) => None,
+            SecretKeyParseError::InvalidKey(error) => Some(error),
         }
     }
 }
@@ -313,14 +1097,564 @@ impl<K: Key> TryFrom<Legacy<K>> for Compressed<K> {
 /// legacy key is in uncompressed format.
 #[derive(Debug, Clone)]
 #[non_exhaustive]
-pub struct KeyNotCompressedError {}
+pub struct KeyNotCompressedError {
+    key_prefix: Option<[u8; 8]>,
+}
+
+impl KeyNotCompressedError {
+    /// Returns the first 8 bytes of the x-coordinate of the offending key.
+    ///
+    /// This identifies the key in logs without storing the whole key. It's
+    /// `None` if the key was a private key since the public key is not
+    /// available without a context and the secret must not be exposed.
+    #[inline]
+    pub fn key_prefix(&self) -> Option<[u8; 8]> { self.key_prefix }
+}
 
 impl fmt::Display for KeyNotCompressedError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        f.write_str("an uncompressed key type is used in a context requiring compressed keys only")
+        f.write_str(
+            "an uncompressed key type is used in a context requiring compressed keys only",
+        )?;
+        if let Some(prefix) = &self.key_prefix {
+            write!(f, " (key {}...)", crate::hex::DisplayHex(prefix))?;
+        }
+        Ok(())
     }
 }
 
 #[cfg(feature = "std")]
 #[cfg_attr(docsrs, doc(cfg(feature = "std")))]
 impl std::error::Error for KeyNotCompressedError {}
+
+#[cfg(test)]
+mod test {
+    use std::format;
+
+    use super::*;
+
+    fn keypair() -> crate::SecpKeyPair {
+        crate::SecpKeyPair::from_seckey_slice(&Secp256k1::signing_only(), &[42; 32]).unwrap()
+    }
+
+    #[test]
+    #[cfg(feature = "bitcoin_hashes")]
+    fn scan_range() {
+        let context = Secp256k1::signing_only();
+        // HASH160 of the compressed generator point (secret key 1)
+        let mut expected = [0u8; 20];
+        crate::hex::decode_to_slice("751e76e8199196d454941c45d1b3a323f1433bd6", &mut expected)
+            .unwrap();
+
+        let mut iter =
+            Compressed::<secp256k1::SecretKey>::scan_range(&context, &crate::Scalar::ZERO, 3);
+        let (secret, hash) = iter.next().unwrap();
+        assert!(secret == crate::Scalar::ONE);
+        assert_eq!(hash, expected);
+        let (secret, hash) = iter.next().unwrap();
+        let key = secp256k1::SecretKey::from_slice(&secret.to_be_bytes()).unwrap();
+        assert_eq!(
+            hash,
+            Compressed::from_raw(key.public_key(&context)).pubkey_hash()
+        );
+        assert!(iter.next().is_none());
+
+        let last = crate::Scalar::MAX;
+        let mut iter = Compressed::<secp256k1::SecretKey>::scan_range(&context, &last, 5);
+        assert!(iter.next().unwrap().0 == last);
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn infer_from_address_prefix() {
+        let segwit = [
+            "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4",
+            "BC1QW508D6QEJXTDG4Y5R3ZARVARY0C5XW7KV8F3T4",
+            "tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx",
+            "bcrt1qw508d6qejxtdg4y5r3zarvary0c5xw7kygt080",
+        ];
+        for address in segwit {
+            assert_eq!(
+                KeyFormat::infer_from_address_prefix(address),
+                Some(KeyFormat::Compressed)
+            );
+        }
+
+        let base58 = [
+            "1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN2",
+            "3J98t1WpEZ73CNmQviecrnyiWrnqRhWNLy",
+            "mipcBbFg9gMiCh81Kj8tqqdgoZub1ZJRfn",
+            "n3GNqMveyvaPvUbH469vDRadqpJMPc84JA",
+            "2MzQwSSnBHWHqSAqtTVQ6v47XtaisrJa1Vc",
+            "",
+        ];
+        for address in base58 {
+            assert_eq!(KeyFormat::infer_from_address_prefix(address), None);
+        }
+
+        let taproot = [
+            "bc1p5d7rjq7g6rdk2yhzks9smlaqtedr4dekq08ge8ztwac72sfr9rusxg3297",
+            "BC1P5D7RJQ7G6RDK2YHZKS9SMLAQTEDR4DEKQ08GE8ZTWAC72SFR9RUSXG3297",
+            "tb1pqqqqp399et2xygdj5xreqhjjvcmzhxw4aywxecjdzew6hylgvsesf3hn0c",
+            "bcrt1p5d7rjq7g6rdk2yhzks9smlaqtedr4dekq08ge8ztwac72sfr9rusuedr2t",
+            "bc1",
+        ];
+        for address in taproot {
+            assert_eq!(KeyFormat::infer_from_address_prefix(address), None);
+        }
+    }
+
+    #[test]
+    fn serialize_keypair_public_key() {
+        let keypair = keypair();
+        let public_key = keypair.public_key();
+
+        let compressed = Compressed::from_raw(keypair);
+        assert_eq!(compressed.serialize_public_key(), public_key.serialize());
+
+        for format in [KeyFormat::Compressed, KeyFormat::Uncompressed] {
+            let legacy = Legacy::from_raw(keypair, format);
+            assert_eq!(
+                legacy.serialize_public_key(),
+                Legacy::from_raw(public_key, format).serialize_public_key()
+            );
+        }
+    }
+
+    #[test]
+    fn reserialize_as() {
+        let key = Legacy::from_raw(keypair().public_key(), KeyFormat::Compressed);
+        let compressed = key.reserialize_as(KeyFormat::Compressed);
+        let uncompressed = key.reserialize_as(KeyFormat::Uncompressed);
+        assert_eq!(compressed, key.serialize_public_key());
+        assert_eq!(uncompressed.len(), 65);
+        assert_eq!(compressed.x_coordinate(), uncompressed.x_coordinate());
+        assert_eq!(key.format(), KeyFormat::Compressed);
+    }
+
+    #[test]
+    fn sign_ecdsa() {
+        let context = Secp256k1::new();
+        let message = secp256k1::Message::from_slice(&[1; 32]).unwrap();
+        let compressed = Compressed::from_raw(keypair());
+        let signature = compressed.sign_ecdsa(&context, &message);
+        assert!(crate::ecdsa::is_low_s(&signature));
+        assert!(context
+            .verify_ecdsa(
+                &message,
+                &signature,
+                compressed.public_key_wrapper().as_ref()
+            )
+            .is_ok());
+
+        let legacy = Legacy::from_raw(keypair(), KeyFormat::Uncompressed);
+        assert_eq!(legacy.sign_ecdsa(&context, &message), signature);
+        assert_eq!(
+            compressed.into_x_only().public_key(),
+            keypair().x_only_public_key().0
+        );
+    }
+
+    #[test]
+    fn derive_child_add() {
+        let context = Secp256k1::new();
+        let secret = Compressed::from_raw(keypair().secret_key());
+        let public = secret.compute_public_key(&context);
+
+        for i in 1..4 {
+            let tweak = crate::Scalar::from_be_bytes([i; 32]).unwrap();
+            let child = secret.derive_child_add(&tweak).unwrap();
+            assert_eq!(
+                child.compute_public_key(&context),
+                public.derive_child_add(&context, &tweak).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    fn compressed_into_xonly() {
+        let context = Secp256k1::verification_only();
+        let key = keypair().public_key();
+
+        for key in [key, key.negate(&context)] {
+            let compressed = Compressed::from_raw(key);
+            let xonly = secp256k1::XOnlyPublicKey::from(compressed);
+            assert_eq!(xonly.serialize(), compressed.serialize_split().1);
+            assert!(compressed.matches_xonly(&xonly));
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "bitcoin_hashes")]
+    fn typed_pubkey_hash() {
+        use bitcoin_hashes::Hash;
+
+        let key = keypair().public_key();
+        for format in [KeyFormat::Compressed, KeyFormat::Uncompressed] {
+            let legacy = Legacy::from_raw(key, format);
+            assert_eq!(legacy.pubkey_hash160().into_inner(), legacy.pubkey_hash());
+        }
+        let compressed = Compressed::from_raw(key);
+        assert_eq!(
+            compressed.pubkey_hash160().into_inner(),
+            compressed.pubkey_hash()
+        );
+        assert_eq!(
+            compressed.pubkey_hash160(),
+            Legacy::from(compressed).pubkey_hash160()
+        );
+    }
+
+    #[test]
+    fn same_point() {
+        fn same<W: KeyWrapper>(a: &W, b: &W) -> bool { a.same_point(b) }
+
+        let keypair = keypair();
+        let other =
+            crate::SecpKeyPair::from_seckey_slice(&Secp256k1::signing_only(), &[43; 32]).unwrap();
+
+        let compressed = Legacy::from_raw(keypair, KeyFormat::Compressed);
+        let uncompressed = Legacy::from_raw(keypair, KeyFormat::Uncompressed);
+        assert!(compressed.same_point(&uncompressed));
+        assert!(same(&compressed, &uncompressed));
+        assert!(!same(
+            &compressed,
+            &Legacy::from_raw(other, KeyFormat::Compressed)
+        ));
+
+        let key = Compressed::from_raw(keypair.public_key());
+        assert!(key.same_point(&key));
+        assert!(same(&key, &key));
+        assert!(!same(&key, &Compressed::from_raw(other.public_key())));
+    }
+
+    #[test]
+    fn add_tweak_with_parity() {
+        let context = Secp256k1::verification_only();
+        let key = Compressed::from_raw(keypair().public_key());
+
+        for i in 1..8 {
+            let tweak = crate::Scalar::from_be_bytes([i; 32]).unwrap();
+            let (tweaked, parity) = key.add_tweak_with_parity(&context, &tweak).unwrap();
+            assert_eq!(tweaked, key.add_tweak(&context, &tweak).unwrap());
+            assert_eq!(
+                tweaked.serialize_public_key()[0],
+                sec1_compressed_prefix(parity)
+            );
+        }
+    }
+
+    #[test]
+    fn format_names() {
+        const COMPRESSED: &str = KeyFormat::Compressed.as_str();
+        assert_eq!(COMPRESSED, "compressed");
+        assert_eq!(KeyFormat::Uncompressed.as_str(), "uncompressed");
+        assert_eq!(format!("{}", KeyFormat::Compressed), "compressed");
+        assert_eq!(format!("{}", KeyFormat::Uncompressed), "uncompressed");
+    }
+
+    #[test]
+    fn keypair_try_into_compressed() {
+        let keypair = keypair();
+
+        let compressed = Compressed::try_from(Legacy::from_raw(keypair, KeyFormat::Compressed));
+        assert_eq!(compressed.unwrap(), Compressed::from_raw(keypair));
+        assert_eq!(
+            Legacy::from(Compressed::from_raw(keypair)),
+            Legacy::from_raw(keypair, KeyFormat::Compressed)
+        );
+        assert!(Compressed::try_from(Legacy::from_raw(keypair, KeyFormat::Uncompressed)).is_err());
+    }
+
+    #[test]
+    fn not_compressed_error_identifies_key() {
+        let keypair = keypair();
+        let public = keypair.public_key();
+        let prefix = &public.serialize_uncompressed()[1..9];
+
+        let error =
+            Compressed::try_from(Legacy::from_raw(public, KeyFormat::Uncompressed)).unwrap_err();
+        assert_eq!(error.key_prefix().as_ref().map(|p| &p[..]), Some(prefix));
+        assert!(format!("{}", error)
+            .ends_with(&format!(" (key {}...)", crate::hex::DisplayHex(prefix))));
+        let error =
+            Compressed::try_from(Legacy::from_raw(keypair, KeyFormat::Uncompressed)).unwrap_err();
+        assert_eq!(error.key_prefix().as_ref().map(|p| &p[..]), Some(prefix));
+
+        let secret = Legacy::from_raw(keypair.secret_key(), KeyFormat::Uncompressed);
+        let error = Compressed::try_from(secret).unwrap_err();
+        assert_eq!(error.key_prefix(), None);
+        assert!(!format!("{}", error).contains("(key"));
+    }
+
+    #[test]
+    #[cfg(feature = "bitcoin_hashes")]
+    fn p2sh_p2wpkh_script() {
+        let one = secp256k1::SecretKey::from_slice(&[[0; 31].as_ref(), &[1]].concat()).unwrap();
+        let key = Compressed::from_raw(one.public_key(&Secp256k1::signing_only()));
+
+        assert_eq!(
+            key.p2sh_p2wpkh_redeem_script(),
+            *b"\x00\x14\x75\x1e\x76\xe8\x19\x91\x96\xd4\x54\x94\x1c\x45\xd1\xb3\xa3\x23\xf1\x43\x3b\xd6"
+        );
+        // address 3JvL6Ymt8MVWiCNHC7oWU6nLeHNJKLZGLN
+        assert_eq!(
+            key.p2sh_p2wpkh_script_pubkey(),
+            *b"\xa9\x14\xbc\xfe\xb7\x28\xb5\x84\x25\x3d\x5f\x3f\x70\xbc\xb7\x80\xe9\xef\x21\x8a\x68\xf4\x87"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "bitcoin_hashes")]
+    fn p2pkh_script() {
+        let one = secp256k1::SecretKey::from_slice(&[[0; 31].as_ref(), &[1]].concat()).unwrap();
+        let key = one.public_key(&Secp256k1::signing_only());
+
+        // addresses 1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH and
+        // 1EHNa6Q4Jz2uvNExL497mE43ikXhwF6kZm
+        let compressed = Legacy::from_raw(key, KeyFormat::Compressed).p2pkh_script();
+        let uncompressed = Legacy::from_raw(key, KeyFormat::Uncompressed).p2pkh_script();
+        assert_eq!(
+            compressed,
+            *b"\x76\xa9\x14\x75\x1e\x76\xe8\x19\x91\x96\xd4\x54\x94\x1c\x45\xd1\xb3\xa3\x23\xf1\x43\x3b\xd6\x88\xac"
+        );
+        assert_eq!(
+            uncompressed,
+            *b"\x76\xa9\x14\x91\xb2\x4b\xf9\xf5\x28\x85\x32\x96\x0a\xc6\x87\xab\xb0\x35\x12\x7b\x1d\x28\xa5\x88\xac"
+        );
+    }
+
+    #[test]
+    fn public_key_bytes() {
+        let key = keypair().public_key();
+        for (format, len) in [(KeyFormat::Compressed, 33), (KeyFormat::Uncompressed, 65)] {
+            let legacy = Legacy::from_raw(key, format);
+            let bytes = legacy.public_key_bytes();
+            assert_eq!(bytes.len(), len);
+            assert!(bytes.eq(legacy.serialize_public_key().iter().copied()));
+        }
+    }
+
+    #[test]
+    fn secret_key_from_slice() {
+        let secret = keypair().secret_key();
+        let bytes = secret.secret_bytes();
+
+        let compressed = Compressed::try_from(&bytes[..]).unwrap();
+        assert_eq!(compressed, Compressed::from_raw(secret));
+        let legacy = Legacy::from_secret_slice(&bytes, KeyFormat::Uncompressed).unwrap();
+        assert_eq!(legacy, Legacy::from_raw(secret, KeyFormat::Uncompressed));
+
+        assert!(matches!(
+            Compressed::<secp256k1::SecretKey>::try_from(&bytes[1..]),
+            Err(SecretKeyParseError::InvalidLength(31))
+        ));
+        assert!(matches!(
+            Legacy::from_secret_slice(&[0; 32], KeyFormat::Compressed),
+            Err(SecretKeyParseError::InvalidKey(_))
+        ));
+    }
+
+    #[test]
+    fn blind_unblind() {
+        let context = Secp256k1::verification_only();
+        let key = Compressed::from_raw(keypair().public_key());
+        let blinding = crate::Scalar::from_be_bytes([7; 32]).unwrap();
+
+        let blinded = key.blind(&context, &blinding).unwrap();
+        assert_ne!(blinded, key);
+        assert_eq!(blinded.unblind(&context, &blinding).unwrap(), key);
+        assert_eq!(
+            key.unblind(&context, &blinding)
+                .unwrap()
+                .blind(&context, &blinding)
+                .unwrap(),
+            key
+        );
+    }
+
+    #[test]
+    fn serialized_public_key_from() {
+        let key = keypair().public_key();
+
+        let legacy = Legacy::from_raw(key, KeyFormat::Uncompressed);
+        let serialized = SerializedPublicKey::from(&legacy);
+        assert_eq!(serialized.len(), 65);
+        assert_eq!(serialized, legacy.serialize_public_key());
+
+        let compressed = Compressed::from_raw(key);
+        let serialized: SerializedPublicKey = (&compressed).into();
+        assert_eq!(serialized.len(), 33);
+        assert_eq!(*serialized, compressed.serialize_public_key()[..]);
+    }
+
+    #[test]
+    fn public_key_no_ctx() {
+        let keypair = keypair();
+        let private = Compressed::from_raw(keypair.secret_key());
+        assert_eq!(
+            private.public_key_no_ctx(),
+            Compressed::from_raw(keypair.public_key())
+        );
+    }
+
+    #[test]
+    fn split_keypair() {
+        let keypair = keypair();
+
+        let (public, private) = Legacy::from_raw(keypair, KeyFormat::Uncompressed).split();
+        assert_eq!(
+            public,
+            Legacy::from_raw(keypair.public_key(), KeyFormat::Uncompressed)
+        );
+        assert_eq!(
+            private,
+            Legacy::from_raw(keypair.secret_key(), KeyFormat::Uncompressed)
+        );
+
+        let (public, private) = Compressed::from_raw(keypair).split();
+        assert_eq!(public, Compressed::from_raw(keypair.public_key()));
+        assert_eq!(private, Compressed::from_raw(keypair.secret_key()));
+    }
+
+    #[test]
+    fn keypair_public_key() {
+        let keypair = keypair();
+        let context = Secp256k1::signing_only();
+
+        let legacy = Legacy::from_raw(keypair, KeyFormat::Uncompressed);
+        assert_eq!(
+            legacy.public_key_wrapper(),
+            legacy.compute_public_key(&context)
+        );
+        let compressed = Compressed::from_raw(keypair);
+        assert_eq!(
+            compressed.public_key_wrapper(),
+            compressed.compute_public_key(&context)
+        );
+    }
+
+    #[test]
+    fn as_ref_public_key() {
+        fn borrow(key: &impl AsRef<secp256k1::PublicKey>) -> secp256k1::PublicKey { *key.as_ref() }
+
+        let key = keypair().public_key();
+        assert_eq!(borrow(&Legacy::from_raw(key, KeyFormat::Uncompressed)), key);
+        assert_eq!(borrow(&Compressed::from_raw(key)), key);
+    }
+
+    const G_COMPRESSED: &str = "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+    const G_UNCOMPRESSED: &str = "0479be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8";
+
+    #[test]
+    fn parse_legacy() {
+        let compressed = G_COMPRESSED
+            .parse::<Legacy<secp256k1::PublicKey>>()
+            .unwrap();
+        assert_eq!(compressed.format(), KeyFormat::Compressed);
+        assert_eq!(
+            format!("0x{}", G_COMPRESSED)
+                .parse::<Legacy<secp256k1::PublicKey>>()
+                .unwrap(),
+            compressed
+        );
+
+        let uncompressed = G_UNCOMPRESSED
+            .parse::<Legacy<secp256k1::PublicKey>>()
+            .unwrap();
+        assert_eq!(uncompressed.format(), KeyFormat::Uncompressed);
+        assert!(uncompressed.eq_key(compressed));
+        assert_eq!(
+            format!("0X{}", G_UNCOMPRESSED)
+                .parse::<Legacy<secp256k1::PublicKey>>()
+                .unwrap(),
+            uncompressed
+        );
+
+        assert!(matches!(
+            "0x0279".parse::<Legacy<secp256k1::PublicKey>>(),
+            Err(PublicKeyParseError::InvalidLength(2))
+        ));
+    }
+
+    #[test]
+    fn reject_off_curve() {
+        // x = 5 is not a valid x-coordinate
+        let mut bytes = [0u8; 33];
+        bytes[0] = 0x02;
+        bytes[32] = 5;
+        assert!(matches!(
+            Legacy::<secp256k1::PublicKey>::try_from(&bytes[..]),
+            Err(PublicKeyParseError::InvalidKey(_))
+        ));
+        assert!(matches!(
+            Compressed::<secp256k1::PublicKey>::try_from(&bytes[..]),
+            Err(PublicKeyParseError::InvalidKey(_))
+        ));
+
+        let mut uncompressed = [0u8; 65];
+        uncompressed[0] = 0x04;
+        assert!(matches!(
+            Legacy::from_sec1_bytes(&uncompressed),
+            Err(PublicKeyParseError::InvalidKey(_))
+        ));
+    }
+
+    #[test]
+    fn parse_compressed() {
+        let key = G_COMPRESSED
+            .parse::<Compressed<secp256k1::PublicKey>>()
+            .unwrap();
+        assert_eq!(
+            format!("0x{}", G_COMPRESSED)
+                .parse::<Compressed<secp256k1::PublicKey>>()
+                .unwrap(),
+            key
+        );
+        assert!(matches!(
+            G_UNCOMPRESSED.parse::<Compressed<secp256k1::PublicKey>>(),
+            Err(PublicKeyParseError::InvalidLength(65))
+        ));
+        assert!(matches!(
+            "0x0g".parse::<Compressed<secp256k1::PublicKey>>(),
+            Err(PublicKeyParseError::InvalidHex(HexError::InvalidChar {
+                index: 1
+            }))
+        ));
+    }
+
+    #[test]
+    fn matches_xonly() {
+        let context = Secp256k1::new();
+        let key = keypair().public_key();
+        let (xonly, _) = key.x_only_public_key();
+
+        assert!(Compressed::from_raw(key).matches_xonly(&xonly));
+        assert!(Compressed::from_raw(key.negate(&context)).matches_xonly(&xonly));
+
+        let other = crate::SecpKeyPair::from_seckey_slice(&context, &[43; 32]).unwrap();
+        assert!(!Compressed::from_raw(key).matches_xonly(&other.x_only_public_key().0));
+    }
+
+    #[test]
+    fn compressed_prefix_roundtrip() {
+        let context = Secp256k1::new();
+        let key = keypair().public_key();
+        for key in [key, key.negate(&context)] {
+            let (xonly, parity) = key.x_only_public_key();
+            let mut serialized = [sec1_compressed_prefix(parity); 33];
+            serialized[1..].copy_from_slice(&xonly.serialize());
+            assert_eq!(secp256k1::PublicKey::from_slice(&serialized).unwrap(), key);
+        }
+    }
+
+    #[test]
+    fn serialize_split() {
+        let key = Compressed::from_raw(keypair().public_key());
+        let (prefix, x) = key.serialize_split();
+
+        let mut recombined = [prefix; 33];
+        recombined[1..].copy_from_slice(&x);
+        assert_eq!(recombined, key.serialize_public_key());
+    }
+}
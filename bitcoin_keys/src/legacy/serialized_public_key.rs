@@ -3,6 +3,10 @@
 //! Because the serialized keys may have different lengths a simple array can
 //! not be used. `Vec<u8>` would've been possible but require allocation (slow,
 //! needs allocator). Our special types avoid this problem.
+//!
+//! The storage is generic over the maximum length so that other key encodings
+//! (such as [`SerializedXOnlyPublicKey`](crate::bip340::SerializedXOnlyPublicKey))
+//! can share the same slice and iterator API.
 
 use core::convert::TryFrom;
 use core::fmt;
@@ -18,13 +22,29 @@ use super::KeyFormat;
 /// Note that this type is a bit large and may be costly to move.
 /// Ideally, you should obtain the slice/iterator as soon after it's returned as
 /// you can and use it instead.
+///
+/// Applications that never handle uncompressed keys should use
+/// [`Compressed`](super::Compressed) instead of [`Legacy`](super::Legacy).
+/// Its `serialize_public_key` returns a plain `[u8; 33]` so this type is not
+/// involved at all. There's intentionally no feature flag shrinking this type
+/// since it would break other crates in the dependency tree relying on
+/// uncompressed keys.
+pub type SerializedPublicKey = FixedKeyArray<65>;
+
+/// Serialized key stored in a fixed-size array.
+///
+/// This is the storage behind [`SerializedPublicKey`] and
+/// [`SerializedXOnlyPublicKey`](crate::bip340::SerializedXOnlyPublicKey), you
+/// should use those aliases rather than this type directly. Arrays of 65 bytes
+/// hold SEC1-encoded keys which use only 33 bytes if compressed, other
+/// lengths are always used whole.
 #[derive(Copy, Clone)] // others must be manual
-pub struct SerializedPublicKey {
+pub struct FixedKeyArray<const N: usize> {
     // Note: we don't need to store Format because it is stored in zeroth byte.
-    data: [u8; 65],
+    data: [u8; N],
 }
 
-impl SerializedPublicKey {
+impl FixedKeyArray<65> {
     /// Serializes given public key.
     ///
     /// This function is intentionally private and written here as opposed to
@@ -53,13 +73,67 @@ impl SerializedPublicKey {
             }
         };
 
-        SerializedPublicKey { data }
+        FixedKeyArray { data }
+    }
+
+    /// Compares the keys by their points, ignoring the format.
+    ///
+    /// The keys are ordered by the x-coordinate first and the parity of the
+    /// y-coordinate second.
+    pub fn cmp_by_point(&self, other: &Self) -> core::cmp::Ordering {
+        self.x_coordinate()
+            .cmp(other.x_coordinate())
+            .then_with(|| self.parity().cmp(&other.parity()))
+    }
+
+    /// Returns the SEC1 prefix byte.
+    ///
+    /// The prefix is `0x04` for uncompressed keys and `0x02` or `0x03` for
+    /// compressed keys with even or odd y-coordinate respectively.
+    #[inline]
+    pub fn prefix(&self) -> u8 { self.data[0] }
+
+    /// Returns the x-coordinate of the point.
+    #[inline]
+    pub fn x_coordinate(&self) -> &[u8; 32] {
+        <&[u8; 32]>::try_from(&self.data[1..33]).expect("the range is 32 bytes long")
     }
 
+    /// Returns the y-coordinate of the point if the key is uncompressed.
+    ///
+    /// Compressed keys only store the parity of the y-coordinate in the
+    /// [`prefix`](Self::prefix) so this returns `None` for them.
+    #[inline]
+    pub fn y_coordinate(&self) -> Option<&[u8; 32]> {
+        if self.data[0] == 4 {
+            Some(<&[u8; 32]>::try_from(&self.data[33..]).expect("the range is 32 bytes long"))
+        } else {
+            None
+        }
+    }
+
+    // 0 for even, 1 for odd
+    fn parity(&self) -> u8 {
+        if self.data[0] == 4 {
+            self.data[64] & 1
+        } else {
+            self.data[0] & 1
+        }
+    }
+}
+
+impl<const N: usize> FixedKeyArray<N> {
+    /// Wraps the serialized key.
+    ///
+    /// For `N == 65` the array must contain a SEC1-encoded key.
+    #[inline]
+    pub(crate) fn from_array(data: [u8; N]) -> Self { FixedKeyArray { data } }
+
     /// Returns the length of the slice.
     ///
-    /// The returned value will be either 33 or 65, depending on the format of
-    /// the key this was created from.
+    /// For [`SerializedPublicKey`] the returned value will be either 33 or 65,
+    /// depending on the format of the key this was created from. Other keys
+    /// always have length `N`.
     #[inline]
     #[allow(clippy::len_without_is_empty)]
     pub fn len(&self) -> usize { self.as_slice().len() }
@@ -70,10 +144,16 @@ impl SerializedPublicKey {
 
     /// Returns the serialized bytes as a slice.
     ///
-    /// The length of the returned slice will be either 33 or 65, depending on
-    /// the format of the key this was created from.
+    /// For [`SerializedPublicKey`] the length of the returned slice will be
+    /// either 33 or 65, depending on the format of the key this was created
+    /// from. Other keys always return the whole array.
     #[inline]
     pub fn as_slice(&self) -> &[u8] {
+        // N is a constant so the branch is removed during monomorphisation.
+        if N != 65 {
+            return &self.data;
+        }
+
         // This produces a beautiful, short, branch-free assembly :)
         //
         // If the key format is uncompressed, the zeroth byte is 4 - and it is 2 or 3
@@ -94,28 +174,57 @@ impl SerializedPublicKey {
     /// only valid for up to `self.len()` bytes.
     #[inline]
     pub fn as_ptr(&self) -> *const u8 { self.as_slice().as_ptr() }
+
+    /// Writes the bytes as lowercase hex into the writer.
+    ///
+    /// This allows producing hex strings without an allocator, e.g. into a
+    /// fixed-capacity string.
+    #[inline]
+    pub fn write_hex_to(&self, writer: &mut impl fmt::Write) -> fmt::Result {
+        write!(writer, "{}", crate::hex::DisplayHex(self.as_slice()))
+    }
+}
+
+/// Parses the serialized bytes back into the key.
+impl TryFrom<SerializedPublicKey> for secp256k1::PublicKey {
+    type Error = secp256k1::Error;
+
+    #[inline]
+    fn try_from(value: SerializedPublicKey) -> Result<Self, Self::Error> {
+        secp256k1::PublicKey::try_from(&value)
+    }
+}
+
+/// Parses the serialized bytes back into the key.
+impl TryFrom<&SerializedPublicKey> for secp256k1::PublicKey {
+    type Error = secp256k1::Error;
+
+    #[inline]
+    fn try_from(value: &SerializedPublicKey) -> Result<Self, Self::Error> {
+        secp256k1::PublicKey::from_slice(value.as_slice())
+    }
 }
 
-impl core::ops::Deref for SerializedPublicKey {
+impl<const N: usize> core::ops::Deref for FixedKeyArray<N> {
     type Target = [u8];
 
     #[inline]
     fn deref(&self) -> &Self::Target { self.as_slice() }
 }
 
-impl AsRef<[u8]> for SerializedPublicKey {
+impl<const N: usize> AsRef<[u8]> for FixedKeyArray<N> {
     #[inline]
     fn as_ref(&self) -> &[u8] { self.as_slice() }
 }
 
-impl core::borrow::Borrow<[u8]> for SerializedPublicKey {
+impl<const N: usize> core::borrow::Borrow<[u8]> for FixedKeyArray<N> {
     #[inline]
     fn borrow(&self) -> &[u8] { self.as_slice() }
 }
 
 // For consideration before API 1.0: should we newtype this to get an iterator
 // returning `u8`?
-impl<'a> IntoIterator for &'a SerializedPublicKey {
+impl<'a, const N: usize> IntoIterator for &'a FixedKeyArray<N> {
     type IntoIter = core::slice::Iter<'a, u8>;
     type Item = &'a u8;
 
@@ -123,31 +232,39 @@ impl<'a> IntoIterator for &'a SerializedPublicKey {
     fn into_iter(self) -> Self::IntoIter { self.iter() }
 }
 
-impl IntoIterator for SerializedPublicKey {
-    type IntoIter = IntoIter;
+impl<const N: usize> IntoIterator for FixedKeyArray<N> {
+    type IntoIter = IntoIter<N>;
     type Item = u8;
 
     #[inline]
     fn into_iter(self) -> Self::IntoIter { IntoIter { key: self, pos: 0 } }
 }
 
-impl PartialEq for SerializedPublicKey {
+impl<const N: usize> PartialEq for FixedKeyArray<N> {
     #[inline]
-    fn eq(&self, other: &SerializedPublicKey) -> bool { self.as_slice() == other.as_slice() }
+    fn eq(&self, other: &FixedKeyArray<N>) -> bool { self.as_slice() == other.as_slice() }
 }
 
-impl Eq for SerializedPublicKey {}
+impl<const N: usize> Eq for FixedKeyArray<N> {}
 
-impl PartialOrd for SerializedPublicKey {
+impl<const N: usize> PartialOrd for FixedKeyArray<N> {
     #[inline]
-    fn partial_cmp(&self, other: &SerializedPublicKey) -> Option<core::cmp::Ordering> {
+    fn partial_cmp(&self, other: &FixedKeyArray<N>) -> Option<core::cmp::Ordering> {
         Some(self.cmp(other))
     }
 }
 
-impl Ord for SerializedPublicKey {
+/// Compares the serialized bytes.
+///
+/// Note that for [`SerializedPublicKey`] the format is significant: all
+/// compressed keys sort before all uncompressed ones and the same point
+/// serialized in different formats is not equal. BIP-67 sorting uses compressed
+/// keys only so this is not a problem there. Use
+/// [`cmp_by_point`](SerializedPublicKey::cmp_by_point) for ordering
+/// that ignores the format.
+impl<const N: usize> Ord for FixedKeyArray<N> {
     #[inline]
-    fn cmp(&self, other: &SerializedPublicKey) -> core::cmp::Ordering {
+    fn cmp(&self, other: &FixedKeyArray<N>) -> core::cmp::Ordering {
         // Optimization inspired by Bitcoin Core code.
         //
         // Explanation: naively, we could call `as_slice()` on both and compare those,
@@ -171,14 +288,14 @@ impl Ord for SerializedPublicKey {
     }
 }
 
-impl core::hash::Hash for SerializedPublicKey {
+impl<const N: usize> core::hash::Hash for FixedKeyArray<N> {
     #[inline]
     fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
         <[u8] as core::hash::Hash>::hash(self.as_slice(), state)
     }
 }
 
-impl fmt::Debug for SerializedPublicKey {
+impl<const N: usize> fmt::Debug for FixedKeyArray<N> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         for b in self {
             write!(f, "{:02x}", b)?;
@@ -188,14 +305,18 @@ impl fmt::Debug for SerializedPublicKey {
 }
 
 /// Owned iterator over bytes of the serialized public key.
-#[derive(Debug, Clone, Eq, PartialEq, Hash)]
-pub struct IntoIter {
-    key: SerializedPublicKey,
+///
+/// Iterators are compared and hashed by their remaining bytes so two
+/// iterators that will yield the same items are equal regardless of how many
+/// items were already consumed.
+#[derive(Debug, Clone)]
+pub struct IntoIter<const N: usize = 65> {
+    key: FixedKeyArray<N>,
     // invariant: pos <= key.len()
     pos: u8,
 }
 
-impl IntoIter {
+impl<const N: usize> IntoIter<N> {
     /// Returns the remaining bytes as a slice.
     #[inline]
     pub fn as_slice(&self) -> &[u8] { &self.key[self.pos()..] }
@@ -206,7 +327,21 @@ impl IntoIter {
     fn pos(&self) -> usize { self.pos.into() }
 }
 
-impl Iterator for IntoIter {
+impl<const N: usize> PartialEq for IntoIter<N> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool { self.as_slice() == other.as_slice() }
+}
+
+impl<const N: usize> Eq for IntoIter<N> {}
+
+impl<const N: usize> core::hash::Hash for IntoIter<N> {
+    #[inline]
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        <[u8] as core::hash::Hash>::hash(self.as_slice(), state)
+    }
+}
+
+impl<const N: usize> Iterator for IntoIter<N> {
     type Item = u8;
 
     #[inline]
@@ -214,7 +349,8 @@ impl Iterator for IntoIter {
         let item = *self.key.get(self.pos())?;
         // The invariant is maintained because we start at zero and only add one if
         // `pos` is less than `self.key.len()`. If `pos == self.key.len()` the
-        // line above returns. no overflow because key len is at most 65
+        // line above returns. no overflow because key len is at most 65 bytes
+        // (we don't use longer keys)
         self.pos += 1;
         debug_assert!(self.pos() <= self.key.len());
         Some(item)
@@ -258,11 +394,11 @@ impl Iterator for IntoIter {
     }
 }
 
-impl ExactSizeIterator for IntoIter {}
+impl<const N: usize> ExactSizeIterator for IntoIter<N> {}
 
 // Once `pos` reaches `len()` `get()` is returning `None` without changing the
 // iterator.
-impl core::iter::FusedIterator for IntoIter {}
+impl<const N: usize> core::iter::FusedIterator for IntoIter<N> {}
 
 #[cfg(feature = "alloc")]
 mod alloc_impls {
@@ -272,41 +408,192 @@ mod alloc_impls {
     use alloc::sync::Arc;
     use alloc::vec::Vec;
 
-    use super::SerializedPublicKey;
+    use super::FixedKeyArray;
 
     /// This conversion allocates
     #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
-    impl From<SerializedPublicKey> for Vec<u8> {
+    impl<const N: usize> From<FixedKeyArray<N>> for Vec<u8> {
         #[inline]
-        fn from(value: SerializedPublicKey) -> Self { value.to_vec() }
+        fn from(value: FixedKeyArray<N>) -> Self { value.to_vec() }
     }
 
     /// This conversion allocates
     #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
-    impl From<SerializedPublicKey> for Box<[u8]> {
+    impl<const N: usize> From<FixedKeyArray<N>> for Box<[u8]> {
         #[inline]
-        fn from(value: SerializedPublicKey) -> Self { value.as_slice().into() }
+        fn from(value: FixedKeyArray<N>) -> Self { value.as_slice().into() }
     }
 
     /// This conversion allocates
     #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
-    impl From<SerializedPublicKey> for Rc<[u8]> {
+    impl<const N: usize> From<FixedKeyArray<N>> for Rc<[u8]> {
         #[inline]
-        fn from(value: SerializedPublicKey) -> Self { value.as_slice().into() }
+        fn from(value: FixedKeyArray<N>) -> Self { value.as_slice().into() }
     }
 
     /// This conversion allocates
     #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
-    impl From<SerializedPublicKey> for Arc<[u8]> {
+    impl<const N: usize> From<FixedKeyArray<N>> for Arc<[u8]> {
         #[inline]
-        fn from(value: SerializedPublicKey) -> Self { value.as_slice().into() }
+        fn from(value: FixedKeyArray<N>) -> Self { value.as_slice().into() }
     }
 
     /// This conversion always produces the [`Owned`](Cow::Owned) variant -
     /// allocates.
     #[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
-    impl<'a> From<SerializedPublicKey> for Cow<'a, [u8]> {
+    impl<'a, const N: usize> From<FixedKeyArray<N>> for Cow<'a, [u8]> {
         #[inline]
-        fn from(value: SerializedPublicKey) -> Self { Cow::Owned(value.into()) }
+        fn from(value: FixedKeyArray<N>) -> Self { Cow::Owned(value.into()) }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use core::fmt::Write;
+
+    use super::*;
+
+    struct StackBuf {
+        data: [u8; 130],
+        len: usize,
+    }
+
+    impl Write for StackBuf {
+        fn write_str(&mut self, s: &str) -> fmt::Result {
+            let end = self.len + s.len();
+            self.data
+                .get_mut(self.len..end)
+                .ok_or(fmt::Error)?
+                .copy_from_slice(s.as_bytes());
+            self.len = end;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn parse_back() {
+        let hex = "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+        let key = hex.parse::<crate::CompressedPublicKey>().unwrap().raw_key();
+
+        for format in [KeyFormat::Compressed, KeyFormat::Uncompressed] {
+            let serialized = SerializedPublicKey::new(key, format);
+            assert_eq!(secp256k1::PublicKey::try_from(&serialized), Ok(key));
+            assert_eq!(secp256k1::PublicKey::try_from(serialized), Ok(key));
+        }
+    }
+
+    #[test]
+    fn into_iter_eq_by_remaining() {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::{Hash, Hasher};
+
+        fn hash<T: Hash>(value: &T) -> u64 {
+            let mut hasher = DefaultHasher::new();
+            value.hash(&mut hasher);
+            hasher.finish()
+        }
+
+        // not valid points but the iterator doesn't care
+        let mut compressed = [0u8; 65];
+        compressed[0] = 0x02;
+        compressed[1..33].copy_from_slice(&[7; 32]);
+        let mut uncompressed = [0x04; 65];
+        uncompressed[33..].copy_from_slice(&[7; 32]);
+
+        let mut left = SerializedPublicKey::from_array(compressed).into_iter();
+        let mut right = SerializedPublicKey::from_array(uncompressed).into_iter();
+        assert_ne!(left, right);
+        left.next();
+        right.nth(32);
+        assert_eq!(left.as_slice(), &[7; 32]);
+        assert_eq!(left, right);
+        assert_eq!(hash(&left), hash(&right));
+    }
+
+    #[test]
+    fn coordinates() {
+        let hex = "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+        let key = hex.parse::<crate::CompressedPublicKey>().unwrap().raw_key();
+
+        let compressed = SerializedPublicKey::new(key, KeyFormat::Compressed);
+        assert_eq!(compressed.prefix(), 0x02);
+        assert_eq!(compressed.x_coordinate()[..], compressed[1..]);
+        assert_eq!(compressed.y_coordinate(), None);
+
+        let uncompressed = SerializedPublicKey::new(key, KeyFormat::Uncompressed);
+        assert_eq!(uncompressed.prefix(), 0x04);
+        assert_eq!(uncompressed.x_coordinate(), compressed.x_coordinate());
+        assert_eq!(
+            uncompressed.y_coordinate().map(|y| &y[..]),
+            Some(&uncompressed[33..])
+        );
+    }
+
+    #[test]
+    fn cmp_by_point() {
+        use core::cmp::Ordering;
+
+        let context = secp256k1::Secp256k1::new();
+        let secret = secp256k1::SecretKey::from_slice(&[42; 32]).unwrap();
+        let key = secp256k1::PublicKey::from_secret_key(&context, &secret);
+        let negated = key.negate(&context);
+
+        let compressed = SerializedPublicKey::new(key, KeyFormat::Compressed);
+        let uncompressed = SerializedPublicKey::new(key, KeyFormat::Uncompressed);
+        assert_ne!(compressed.cmp(&uncompressed), Ordering::Equal);
+        assert_eq!(compressed.cmp_by_point(&uncompressed), Ordering::Equal);
+
+        let negated_compressed = SerializedPublicKey::new(negated, KeyFormat::Compressed);
+        let negated_uncompressed = SerializedPublicKey::new(negated, KeyFormat::Uncompressed);
+        let order = compressed.cmp(&negated_compressed);
+        assert_ne!(order, Ordering::Equal);
+        assert_eq!(compressed.cmp_by_point(&negated_uncompressed), order);
+        assert_eq!(uncompressed.cmp_by_point(&negated_compressed), order);
+
+        // `Ord` compares the prefix holding the parity first so it disagrees
+        // with `cmp_by_point` for an even key with a greater x-coordinate than
+        // an odd key.
+        let other = secp256k1::PublicKey::from_secret_key(
+            &context,
+            &secp256k1::SecretKey::from_slice(&[43; 32]).unwrap(),
+        );
+        let (high, low) = if key.serialize()[1..] > other.serialize()[1..] {
+            (key, other)
+        } else {
+            (other, key)
+        };
+        let even = |key: secp256k1::PublicKey| {
+            if key.serialize()[0] == 0x02 {
+                key
+            } else {
+                key.negate(&context)
+            }
+        };
+        let high = SerializedPublicKey::new(even(high), KeyFormat::Compressed);
+        let low = SerializedPublicKey::new(even(low).negate(&context), KeyFormat::Compressed);
+        assert_eq!(high.prefix(), 0x02);
+        assert_eq!(low.prefix(), 0x03);
+        assert_eq!(high.cmp(&low), Ordering::Less);
+        assert_eq!(high.cmp_by_point(&low), Ordering::Greater);
+    }
+
+    #[test]
+    fn write_hex() {
+        let hex = "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+        let key = hex.parse::<crate::CompressedPublicKey>().unwrap();
+        let serialized = SerializedPublicKey::from(&key);
+
+        let mut buf = StackBuf {
+            data: [0; 130],
+            len: 0,
+        };
+        serialized.write_hex_to(&mut buf).unwrap();
+        assert_eq!(&buf.data[..buf.len], hex.as_bytes());
+
+        let mut buf = StackBuf {
+            data: [0; 130],
+            len: 100,
+        };
+        assert!(serialized.write_hex_to(&mut buf).is_err());
     }
 }
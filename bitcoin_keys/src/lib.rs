@@ -18,16 +18,41 @@
 #[cfg(feature = "alloc")]
 extern crate alloc;
 
-#[cfg(feature = "std")]
+#[cfg(any(feature = "std", test))]
 extern crate std;
 
+pub mod any_key;
 pub mod bip340;
+pub mod ecdsa;
+pub mod error;
+mod hex;
 pub mod legacy;
+pub mod origin;
+pub mod prelude;
+pub mod scalar;
+pub mod script;
+#[cfg(all(feature = "serde", feature = "alloc"))]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "serde", feature = "alloc"))))]
+pub mod serde;
+pub mod signer;
+mod stack_context;
 
+pub use any_key::AnyPublicKey;
 pub use bip340::{XOnlyKeyPair, XOnlyPrivateKey, XOnlyPublicKey};
+pub use error::KeyParseError;
+pub use hex::HexError;
+pub use scalar::NonZeroScalar;
 pub use secp256k1::scalar::Scalar;
 pub use secp256k1::{self};
 
+/// Key pair type of the secp256k1 version this crate is built against.
+///
+/// secp256k1 0.28 renamed `KeyPair` to `Keypair`. This crate refers to the type
+/// only through this alias so the rename itself won't affect code using it.
+/// Note that the crate currently supports secp256k1 0.27 only, upgrading
+/// requires adapting to other API changes as well.
+pub type SecpKeyPair = secp256k1::KeyPair;
+
 /// Public key that may be serialized as uncompressed, used in legacy addresses
 /// only.
 ///
@@ -59,10 +84,10 @@ pub type CompressedPrivateKey = legacy::Compressed<secp256k1::SecretKey>;
 ///
 /// You probably want to use this alias instead of explicitly writing out the
 /// type.
-pub type LegacyKeyPair = legacy::Legacy<secp256k1::KeyPair>;
+pub type LegacyKeyPair = legacy::Legacy<SecpKeyPair>;
 
 /// Key pair that is always serialized as compressed.
 ///
 /// You probably want to use this alias instead of explicitly writing out the
 /// type.
-pub type CompressedKeyPair = legacy::Compressed<secp256k1::KeyPair>;
+pub type CompressedKeyPair = legacy::Compressed<SecpKeyPair>;
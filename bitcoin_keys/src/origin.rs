@@ -0,0 +1,254 @@
+//! Keys annotated with their BIP-32 origin.
+//!
+//! PSBTs and wallet descriptors carry each key together with the fingerprint of
+//! the master key and the derivation path used to obtain it. Signers use this
+//! information to find out which keys they control. [`WithOrigin`] bundles the
+//! key with its origin so that they can't get separated accidentally.
+
+use core::fmt;
+
+/// Key together with the fingerprint of its master key and derivation path.
+///
+/// The key may be any key type from this crate. The path is stored in `P`
+/// which may be any type holding a slice of child numbers. This keeps the type
+/// usable without an allocator: fixed-size arrays or borrowed slices work just
+/// as well as `Vec<u32>`. Hardened child numbers have the most significant bit
+/// set, as defined in BIP-32.
+///
+/// With the `serde` feature the type is serialized as a struct with `key`,
+/// `fingerprint` and `path` fields.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub struct WithOrigin<K, P: AsRef<[u32]>> {
+    key: K,
+    fingerprint: [u8; 4],
+    path: P,
+}
+
+impl<K, P: AsRef<[u32]>> WithOrigin<K, P> {
+    /// Attaches the origin to the key.
+    ///
+    /// `fingerprint` is the first four bytes of the HASH160 of the master
+    /// public key and `path` is the derivation path from the master key.
+    #[inline]
+    pub fn new(key: K, fingerprint: [u8; 4], path: P) -> Self {
+        WithOrigin {
+            key,
+            fingerprint,
+            path,
+        }
+    }
+
+    /// Borrows the key.
+    #[inline]
+    pub fn key(&self) -> &K { &self.key }
+
+    /// Returns the fingerprint of the master key.
+    #[inline]
+    pub fn fingerprint(&self) -> [u8; 4] { self.fingerprint }
+
+    /// Returns the derivation path from the master key.
+    #[inline]
+    pub fn path(&self) -> &[u32] { self.path.as_ref() }
+
+    /// Returns the key discarding the origin.
+    #[inline]
+    pub fn into_key(self) -> K { self.key }
+
+    /// Returns the key, the fingerprint and the path.
+    #[inline]
+    pub fn into_parts(self) -> (K, [u8; 4], P) { (self.key, self.fingerprint, self.path) }
+
+    /// Converts the key keeping the origin.
+    ///
+    /// This is useful to e.g. compute a public key from a private key.
+    #[inline]
+    pub fn map_key<T, F: FnOnce(K) -> T>(self, f: F) -> WithOrigin<T, P> {
+        WithOrigin {
+            key: f(self.key),
+            fingerprint: self.fingerprint,
+            path: self.path,
+        }
+    }
+}
+
+/// Displays the origin in the descriptor notation followed by the key.
+///
+/// For instance `[d34db33f/44'/0'/0']` followed by the key. Hardened child
+/// numbers are marked with `'`.
+impl<K: fmt::Display, P: AsRef<[u32]>> fmt::Display for WithOrigin<K, P> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "[{}", crate::hex::DisplayHex(&self.fingerprint))?;
+        for child in self.path() {
+            if child & HARDENED != 0 {
+                write!(f, "/{}'", child & !HARDENED)?;
+            } else {
+                write!(f, "/{}", child)?;
+            }
+        }
+        write!(f, "]{}", self.key)
+    }
+}
+
+const HARDENED: u32 = 1 << 31;
+
+#[cfg(feature = "serde")]
+mod serde_impl {
+    use core::fmt;
+    use core::marker::PhantomData;
+
+    use serde_crate::de::{self, MapAccess, SeqAccess};
+    use serde_crate::ser::SerializeStruct;
+    use serde_crate::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::WithOrigin;
+
+    const FIELDS: &[&str] = &["key", "fingerprint", "path"];
+
+    #[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+    impl<K: Serialize, P: AsRef<[u32]>> Serialize for WithOrigin<K, P> {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut state = serializer.serialize_struct("WithOrigin", FIELDS.len())?;
+            state.serialize_field("key", &self.key)?;
+            state.serialize_field("fingerprint", &self.fingerprint)?;
+            state.serialize_field("path", self.path.as_ref())?;
+            state.end()
+        }
+    }
+
+    #[cfg_attr(docsrs, doc(cfg(feature = "serde")))]
+    impl<'de, K, P> Deserialize<'de> for WithOrigin<K, P>
+    where
+        K: Deserialize<'de>,
+        P: Deserialize<'de> + AsRef<[u32]>,
+    {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            deserializer.deserialize_struct("WithOrigin", FIELDS, WithOriginVisitor(PhantomData))
+        }
+    }
+
+    enum Field {
+        Key,
+        Fingerprint,
+        Path,
+    }
+
+    impl<'de> Deserialize<'de> for Field {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            deserializer.deserialize_identifier(FieldVisitor)
+        }
+    }
+
+    struct FieldVisitor;
+
+    impl<'de> de::Visitor<'de> for FieldVisitor {
+        type Value = Field;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("`key`, `fingerprint` or `path`")
+        }
+
+        fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+            match value {
+                "key" => Ok(Field::Key),
+                "fingerprint" => Ok(Field::Fingerprint),
+                "path" => Ok(Field::Path),
+                _ => Err(E::unknown_field(value, FIELDS)),
+            }
+        }
+    }
+
+    struct WithOriginVisitor<K, P>(PhantomData<(K, P)>);
+
+    impl<'de, K, P> de::Visitor<'de> for WithOriginVisitor<K, P>
+    where
+        K: Deserialize<'de>,
+        P: Deserialize<'de> + AsRef<[u32]>,
+    {
+        type Value = WithOrigin<K, P>;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a key with its fingerprint and derivation path")
+        }
+
+        // compact formats serialize structs as sequences
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            let key = match seq.next_element::<K>()? {
+                Some(key) => key,
+                None => return Err(de::Error::invalid_length(0, &self)),
+            };
+            let fingerprint = match seq.next_element::<[u8; 4]>()? {
+                Some(fingerprint) => fingerprint,
+                None => return Err(de::Error::invalid_length(1, &self)),
+            };
+            let path = match seq.next_element::<P>()? {
+                Some(path) => path,
+                None => return Err(de::Error::invalid_length(2, &self)),
+            };
+            Ok(WithOrigin::new(key, fingerprint, path))
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+            let mut key = None;
+            let mut fingerprint = None;
+            let mut path = None;
+            while let Some(field) = map.next_key::<Field>()? {
+                match field {
+                    Field::Key if key.is_some() => return Err(de::Error::duplicate_field("key")),
+                    Field::Key => key = Some(map.next_value::<K>()?),
+                    Field::Fingerprint if fingerprint.is_some() => {
+                        return Err(de::Error::duplicate_field("fingerprint"))
+                    }
+                    Field::Fingerprint => fingerprint = Some(map.next_value::<[u8; 4]>()?),
+                    Field::Path if path.is_some() => {
+                        return Err(de::Error::duplicate_field("path"))
+                    }
+                    Field::Path => path = Some(map.next_value::<P>()?),
+                }
+            }
+            let key = match key {
+                Some(key) => key,
+                None => return Err(de::Error::missing_field("key")),
+            };
+            let fingerprint = match fingerprint {
+                Some(fingerprint) => fingerprint,
+                None => return Err(de::Error::missing_field("fingerprint")),
+            };
+            let path = match path {
+                Some(path) => path,
+                None => return Err(de::Error::missing_field("path")),
+            };
+            Ok(WithOrigin::new(key, fingerprint, path))
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::string::ToString;
+
+    use super::*;
+    use crate::bip340::XOnlyKey;
+
+    #[test]
+    fn accessors_and_display() {
+        let hex = "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+        let key = hex.parse::<XOnlyKey>().unwrap();
+        let path = [86 | HARDENED, HARDENED, HARDENED, 0, 5];
+        let with_origin = WithOrigin::new(key, [0xd3, 0x4d, 0xb3, 0x3f], path);
+
+        assert_eq!(*with_origin.key(), key);
+        assert_eq!(with_origin.fingerprint(), [0xd3, 0x4d, 0xb3, 0x3f]);
+        assert_eq!(with_origin.path(), &path);
+        assert_eq!(
+            with_origin.to_string(),
+            ["[d34db33f/86'/0'/0'/0/5]", hex].concat()
+        );
+
+        let borrowed = WithOrigin::new(key, [0; 4], &path[..2]);
+        assert_eq!(borrowed.map_key(XOnlyKey::into_inner).path(), &path[..2]);
+        assert_eq!(
+            with_origin.into_parts(),
+            (key, [0xd3, 0x4d, 0xb3, 0x3f], path)
+        );
+    }
+}
@@ -0,0 +1,33 @@
+//! Commonly used types.
+//!
+//! Importing everything from this module brings the key types and the type
+//! aliases for the wrappers into scope:
+//!
+//! ```
+//! use bitcoin_keys::prelude::*;
+//!
+//! let key = "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798"
+//!     .parse::<CompressedPublicKey>()
+//!     .unwrap();
+//! let legacy = LegacyPublicKey::from(key);
+//! assert_eq!(legacy.format(), KeyFormat::Compressed);
+//! ```
+//!
+//! The [`Key`](crate::legacy::Key), [`PublicKey`](crate::legacy::PublicKey)
+//! and [`PrivateKey`](crate::legacy::PrivateKey) traits are intentionally not
+//! included. All methods of the wrappers are inherent so the traits are only
+//! needed to write generic code and glob-importing `PublicKey` would clash
+//! with `secp256k1::PublicKey`.
+
+pub use crate::any_key::AnyPublicKey;
+#[cfg(feature = "bitcoin_hashes")]
+pub use crate::bip340::{TweakedKeyPair, UntweakedKeyPair};
+pub use crate::bip340::{
+    XOnlyKey, XOnlyKeyMaterial, XOnlyKeyPair, XOnlyPrivateKey, XOnlyPublicKey,
+};
+pub use crate::legacy::{Compressed, KeyFormat, Legacy};
+pub use crate::origin::WithOrigin;
+pub use crate::{
+    CompressedKeyPair, CompressedPrivateKey, CompressedPublicKey, LegacyKeyPair, LegacyPrivateKey,
+    LegacyPublicKey, Scalar,
+};
@@ -0,0 +1,74 @@
+//! Scalars with additional guarantees.
+
+use crate::Scalar;
+
+/// Scalar that is guaranteed to not be zero.
+///
+/// Unlike [`Scalar`], a non-zero scalar is always a valid secret key so it can
+/// be converted into [`secp256k1::SecretKey`] infallibly. Operations that
+/// reject zero, such as multiplicative tweaking, accept this type to move the
+/// check from each call to the construction.
+///
+/// Similarly to [`Scalar`] this doesn't implement `Debug` because it may
+/// contain secret data.
+#[derive(Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub struct NonZeroScalar(Scalar);
+
+impl NonZeroScalar {
+    /// Returns the scalar if it's not zero.
+    #[inline]
+    pub fn new(scalar: Scalar) -> Option<Self> {
+        if scalar == Scalar::ZERO {
+            None
+        } else {
+            Some(NonZeroScalar(scalar))
+        }
+    }
+
+    /// Returns the underlying scalar.
+    #[inline]
+    pub fn get(self) -> Scalar { self.0 }
+
+    /// Converts the scalar into a secret key.
+    #[inline]
+    pub fn to_secret_key(self) -> secp256k1::SecretKey {
+        secp256k1::SecretKey::from_slice(&self.0.to_be_bytes())
+            .expect("non-zero scalar is always a valid secret key")
+    }
+}
+
+impl From<NonZeroScalar> for Scalar {
+    #[inline]
+    fn from(value: NonZeroScalar) -> Self { value.0 }
+}
+
+impl From<NonZeroScalar> for secp256k1::SecretKey {
+    #[inline]
+    fn from(value: NonZeroScalar) -> Self { value.to_secret_key() }
+}
+
+/// Secret keys are never zero so this can't fail.
+impl From<secp256k1::SecretKey> for NonZeroScalar {
+    #[inline]
+    fn from(value: secp256k1::SecretKey) -> Self { NonZeroScalar(value.into()) }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn rejects_zero() {
+        assert!(NonZeroScalar::new(Scalar::ZERO).is_none());
+
+        let one = NonZeroScalar::new(Scalar::ONE).unwrap();
+        assert!(one.get() == Scalar::ONE);
+        let mut expected = [0; 32];
+        expected[31] = 1;
+        assert_eq!(one.to_secret_key().secret_bytes(), expected);
+
+        let max = NonZeroScalar::new(Scalar::MAX).unwrap();
+        let secret = secp256k1::SecretKey::from(max);
+        assert!(NonZeroScalar::from(secret) == max);
+    }
+}
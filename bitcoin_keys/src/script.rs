@@ -0,0 +1,228 @@
+//! Minimal script assembly.
+//!
+//! This is not a full-featured script library, it only provides what's needed
+//! to assemble scripts containing keys produced by this crate. The fixed-size
+//! helpers on the key types (e.g. `p2pkh_script`, available with the
+//! `bitcoin_hashes` feature) don't allocate and should be preferred for
+//! standard scripts. [`ScriptBuf`] is intended for custom scripts and
+//! [`SerializeToScript`] allows writing script builders generic over the kind
+//! of key.
+
+#[cfg(feature = "alloc")]
+use alloc::vec::Vec;
+#[cfg(feature = "alloc")]
+use core::convert::TryFrom;
+
+#[cfg(feature = "alloc")]
+use crate::legacy::SerializedPublicKey;
+use crate::legacy::{Compressed, Legacy};
+use crate::XOnlyPublicKey;
+
+#[cfg(feature = "alloc")]
+const OP_PUSHDATA1: u8 = 0x4c;
+#[cfg(feature = "alloc")]
+const OP_PUSHDATA2: u8 = 0x4d;
+#[cfg(feature = "alloc")]
+const OP_PUSHDATA4: u8 = 0x4e;
+
+/// Keys that can be pushed into a Bitcoin script.
+pub trait SerializeToScript {
+    /// Writes the push opcode followed by the serialized key into the buffer.
+    fn write_to_script(&self, buf: &mut impl Extend<u8>);
+}
+
+/// Pushes the key in its format.
+impl SerializeToScript for Legacy<secp256k1::PublicKey> {
+    #[inline]
+    fn write_to_script(&self, buf: &mut impl Extend<u8>) {
+        push_key(&self.serialize_public_key(), buf)
+    }
+}
+
+/// Pushes the 33-byte compressed key.
+impl SerializeToScript for Compressed<secp256k1::PublicKey> {
+    #[inline]
+    fn write_to_script(&self, buf: &mut impl Extend<u8>) {
+        push_key(&self.serialize_public_key(), buf)
+    }
+}
+
+/// Pushes the 32-byte x-only key as used in Taproot scripts.
+impl SerializeToScript for XOnlyPublicKey {
+    #[inline]
+    fn write_to_script(&self, buf: &mut impl Extend<u8>) { push_key(&self.serialize(), buf) }
+}
+
+/// Pushes the serialized key, which is at most 65 bytes long.
+fn push_key(key: &[u8], buf: &mut impl Extend<u8>) {
+    debug_assert!(key.len() <= 75);
+    buf.extend(core::iter::once(key.len() as u8).chain(key.iter().copied()));
+}
+
+/// Owned script being assembled.
+///
+/// Push operations use the shortest length prefix able to represent the data
+/// but don't replace small numbers with `OP_N` opcodes.
+#[cfg(feature = "alloc")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloc")))]
+#[derive(Debug, Clone, Default, Eq, PartialEq, Hash)]
+pub struct ScriptBuf {
+    bytes: Vec<u8>,
+}
+
+#[cfg(feature = "alloc")]
+impl ScriptBuf {
+    /// Creates an empty script.
+    #[inline]
+    pub fn new() -> Self { ScriptBuf::default() }
+
+    /// Appends the opcode.
+    #[inline]
+    pub fn push_opcode(&mut self, opcode: u8) { self.bytes.push(opcode); }
+
+    /// Appends the data prefixed with the appropriate push opcode.
+    ///
+    /// # Panics
+    ///
+    /// If the length of the data doesn't fit into `u32`.
+    pub fn push_slice(&mut self, data: &[u8]) {
+        let len = data.len();
+        if len < usize::from(OP_PUSHDATA1) {
+            // len is less than 76
+            self.bytes.push(len as u8);
+        } else if let Ok(len) = u8::try_from(len) {
+            self.bytes.push(OP_PUSHDATA1);
+            self.bytes.push(len);
+        } else if let Ok(len) = u16::try_from(len) {
+            self.bytes.push(OP_PUSHDATA2);
+            self.bytes.extend_from_slice(&len.to_le_bytes());
+        } else {
+            let len = u32::try_from(len).expect("data too long to push");
+            self.bytes.push(OP_PUSHDATA4);
+            self.bytes.extend_from_slice(&len.to_le_bytes());
+        }
+        self.bytes.extend_from_slice(data);
+    }
+
+    /// Appends the serialized public key prefixed with the push opcode.
+    #[inline]
+    pub fn push_key(&mut self, key: &SerializedPublicKey) { push_key(key, self) }
+
+    /// Returns the bytes of the script.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] { &self.bytes }
+
+    /// Returns the bytes of the script.
+    #[inline]
+    pub fn into_bytes(self) -> Vec<u8> { self.bytes }
+}
+
+/// Appends raw bytes without any push opcodes.
+#[cfg(feature = "alloc")]
+impl Extend<u8> for ScriptBuf {
+    #[inline]
+    fn extend<I: IntoIterator<Item = u8>>(&mut self, iter: I) { self.bytes.extend(iter) }
+}
+
+#[cfg(feature = "alloc")]
+impl AsRef<[u8]> for ScriptBuf {
+    #[inline]
+    fn as_ref(&self) -> &[u8] { self.as_bytes() }
+}
+
+#[cfg(feature = "alloc")]
+impl From<ScriptBuf> for Vec<u8> {
+    #[inline]
+    fn from(value: ScriptBuf) -> Self { value.into_bytes() }
+}
+
+#[cfg(test)]
+mod test {
+    use std::vec;
+
+    use super::*;
+    use crate::CompressedPublicKey;
+
+    #[cfg(feature = "alloc")]
+    const OP_CHECKSIG: u8 = 0xac;
+
+    #[test]
+    fn serialize_to_script() {
+        let hex = "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+        let key = hex.parse::<CompressedPublicKey>().unwrap();
+        let uncompressed =
+            crate::LegacyPublicKey::from_raw(key.raw_key(), crate::legacy::KeyFormat::Uncompressed);
+        let xonly = XOnlyPublicKey::from(key);
+
+        let mut buf = vec![];
+        key.write_to_script(&mut buf);
+        assert_eq!(buf[0], 33);
+        assert_eq!(buf[1..], key.serialize_public_key());
+
+        let mut buf = vec![];
+        uncompressed.write_to_script(&mut buf);
+        assert_eq!(buf[0], 65);
+        assert_eq!(buf[1..], *uncompressed.serialize_public_key());
+
+        let mut buf = vec![];
+        xonly.write_to_script(&mut buf);
+        assert_eq!(buf[0], 32);
+        assert_eq!(buf[1..], key.serialize_public_key()[1..]);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn p2pk() {
+        let hex = "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+        let key = hex.parse::<CompressedPublicKey>().unwrap();
+        let mut script = ScriptBuf::new();
+        script.push_key(&(&key).into());
+        script.push_opcode(OP_CHECKSIG);
+
+        let mut expected = [0u8; 35];
+        expected[0] = 0x21;
+        crate::hex::decode_to_slice(hex, &mut expected[1..34]).unwrap();
+        expected[34] = OP_CHECKSIG;
+        assert_eq!(script.as_bytes(), &expected[..]);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn push_lengths() {
+        let mut script = ScriptBuf::new();
+        script.push_slice(&[]);
+        script.push_slice(&[0xab; 75]);
+        assert_eq!(script.as_bytes()[..2], [0x00, 0x4b]);
+        assert_eq!(script.as_bytes().len(), 77);
+
+        let mut script = ScriptBuf::new();
+        script.push_slice(&[0xab; 76]);
+        assert_eq!(script.as_bytes()[..2], [OP_PUSHDATA1, 76]);
+
+        let mut script = ScriptBuf::new();
+        script.push_slice(&vec![0xab; 256]);
+        assert_eq!(script.as_bytes()[..3], [OP_PUSHDATA2, 0x00, 0x01]);
+
+        let mut script = ScriptBuf::new();
+        script.push_slice(&vec![0xab; 0x10000]);
+        assert_eq!(script.as_bytes()[..5], [
+            OP_PUSHDATA4,
+            0x00,
+            0x00,
+            0x01,
+            0x00
+        ]);
+        assert_eq!(script.into_bytes().len(), 0x10005);
+    }
+
+    #[test]
+    #[cfg(feature = "alloc")]
+    fn extend() {
+        let mut script = ScriptBuf::new();
+        script.extend([0x76, 0xa9]);
+        script.push_slice(&[0; 20]);
+        script.extend(vec![0x88, OP_CHECKSIG]);
+        assert_eq!(script.as_bytes().len(), 25);
+        assert_eq!(script.as_bytes()[2], 20);
+    }
+}
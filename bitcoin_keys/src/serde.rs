@@ -0,0 +1,295 @@
+//! Helpers for serializing keys and scalars as hex strings using serde.
+//!
+//! The modules in here are intended to be used with the `#[serde(with = ...)]`
+//! attribute, which lets each field choose its own representation:
+//!
+//! ```ignore
+//! use bitcoin_keys::{CompressedPublicKey, Scalar};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Commitment {
+//!     #[serde(with = "bitcoin_keys::serde::pubkey_as_hex")]
+//!     key: CompressedPublicKey,
+//!     #[serde(with = "bitcoin_keys::serde::scalar_as_hex")]
+//!     tweak: Scalar,
+//! }
+//! ```
+//!
+//! The strings are serialized without the `0x` prefix but it's accepted when
+//! deserializing. Deserialization doesn't allocate. Serialization uses
+//! [`Serializer::collect_str`](serde_crate::Serializer::collect_str) whose
+//! default implementation formats the value into a temporary `String` when
+//! serde is built with `std` or `alloc`, which is the case whenever this
+//! module is available. Serializers writing strings directly, such as most
+//! text formats, override it and don't allocate.
+
+use core::fmt;
+use core::marker::PhantomData;
+use core::str::FromStr;
+
+use serde_crate::de;
+
+/// Visitor parsing the value from both borrowed and owned strings.
+struct FromStrVisitor<T> {
+    expecting: &'static str,
+    _phantom: PhantomData<T>,
+}
+
+impl<'de, T> de::Visitor<'de> for FromStrVisitor<T>
+where
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    type Value = T;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result { f.write_str(self.expecting) }
+
+    // borrowed and owned strings are forwarded here by default
+    fn visit_str<E: de::Error>(self, s: &str) -> Result<Self::Value, E> {
+        s.parse().map_err(E::custom)
+    }
+}
+
+/// Serializes [`Scalar`](crate::Scalar) as 64 big-endian hex digits.
+pub mod scalar_as_hex {
+    use core::fmt;
+    use core::marker::PhantomData;
+    use core::str::FromStr;
+
+    use serde_crate::{Deserializer, Serializer};
+
+    use super::FromStrVisitor;
+    use crate::hex::{self, DisplayHex, HexError};
+    use crate::Scalar;
+
+    /// Serializes the scalar.
+    pub fn serialize<S: Serializer>(scalar: &Scalar, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&DisplayHex(&scalar.to_be_bytes()))
+    }
+
+    /// Deserializes the scalar.
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Scalar, D::Error> {
+        let visitor = FromStrVisitor::<HexScalar> {
+            expecting: "a scalar encoded as 64 hex digits",
+            _phantom: PhantomData,
+        };
+        deserializer.deserialize_str(visitor).map(|scalar| scalar.0)
+    }
+
+    // Scalar doesn't implement FromStr so we need a local type.
+    struct HexScalar(Scalar);
+
+    impl FromStr for HexScalar {
+        type Err = ScalarParseError;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            let s = hex::strip_prefix(s);
+            let len = hex::decoded_len(s).map_err(ScalarParseError::InvalidHex)?;
+            if len != 32 {
+                return Err(ScalarParseError::InvalidLength(len));
+            }
+            let mut bytes = [0u8; 32];
+            hex::decode_to_slice(s, &mut bytes).map_err(ScalarParseError::InvalidHex)?;
+            Scalar::from_be_bytes(bytes)
+                .map(HexScalar)
+                .map_err(|_| ScalarParseError::OutOfRange)
+        }
+    }
+
+    enum ScalarParseError {
+        InvalidHex(HexError),
+        InvalidLength(usize),
+        OutOfRange,
+    }
+
+    impl fmt::Display for ScalarParseError {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            match self {
+                ScalarParseError::InvalidHex(error) => {
+                    write!(f, "invalid hex encoding of scalar: {}", error)
+                }
+                ScalarParseError::InvalidLength(len) => {
+                    write!(
+                        f,
+                        "invalid scalar length {}, the length must be 32 bytes",
+                        len
+                    )
+                }
+                ScalarParseError::OutOfRange => f.write_str("the scalar is out of range"),
+            }
+        }
+    }
+}
+
+/// Serializes public keys as hex strings.
+///
+/// This works with [`Legacy`](crate::legacy::Legacy) and
+/// [`Compressed`](crate::legacy::Compressed) public keys as well as
+/// [`AnyPublicKey`](crate::AnyPublicKey). The key is serialized in its own
+/// format so legacy uncompressed keys produce 130 hex digits.
+pub mod pubkey_as_hex {
+    use core::fmt;
+    use core::marker::PhantomData;
+
+    use serde_crate::{Deserializer, Serializer};
+
+    use super::FromStrVisitor;
+    use crate::hex::DisplayHex;
+
+    /// Public key types supported by this module.
+    pub trait HexPublicKey: sealed::HexPublicKey {}
+
+    mod sealed {
+        use core::str::FromStr;
+
+        use crate::legacy::{Compressed, Legacy};
+        use crate::AnyPublicKey;
+
+        pub trait HexPublicKey: FromStr {
+            fn with_bytes<R>(&self, f: impl FnOnce(&[u8]) -> R) -> R;
+        }
+
+        impl HexPublicKey for Legacy<secp256k1::PublicKey> {
+            fn with_bytes<R>(&self, f: impl FnOnce(&[u8]) -> R) -> R {
+                f(&self.serialize_public_key())
+            }
+        }
+
+        impl HexPublicKey for Compressed<secp256k1::PublicKey> {
+            fn with_bytes<R>(&self, f: impl FnOnce(&[u8]) -> R) -> R {
+                f(&self.serialize_public_key())
+            }
+        }
+
+        impl HexPublicKey for AnyPublicKey {
+            fn with_bytes<R>(&self, f: impl FnOnce(&[u8]) -> R) -> R { f(&self.serialize()) }
+        }
+    }
+
+    impl HexPublicKey for crate::legacy::Legacy<secp256k1::PublicKey> {}
+    impl HexPublicKey for crate::legacy::Compressed<secp256k1::PublicKey> {}
+    impl HexPublicKey for crate::AnyPublicKey {}
+
+    /// Serializes the public key.
+    pub fn serialize<K: HexPublicKey, S: Serializer>(
+        key: &K,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        key.with_bytes(|bytes| serializer.collect_str(&DisplayHex(bytes)))
+    }
+
+    /// Deserializes the public key.
+    pub fn deserialize<'de, K, D>(deserializer: D) -> Result<K, D::Error>
+    where
+        K: HexPublicKey,
+        K::Err: fmt::Display,
+        D: Deserializer<'de>,
+    {
+        let visitor = FromStrVisitor::<K> {
+            expecting: "a hex-encoded public key",
+            _phantom: PhantomData,
+        };
+        deserializer.deserialize_str(visitor)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::string::{String, ToString};
+
+    use serde_crate::de::value::{BorrowedStrDeserializer, Error};
+    use serde_crate::de::IntoDeserializer;
+
+    use super::*;
+    use crate::{AnyPublicKey, CompressedPublicKey, LegacyPublicKey, Scalar};
+
+    // `fmt::Formatter` implements `Serializer` writing strings into the output
+    // so it's used here to avoid depending on a serialization format.
+    fn serialize(serialize: impl Fn(&mut fmt::Formatter) -> fmt::Result) -> String {
+        struct Serialized<F>(F);
+
+        impl<F: Fn(&mut fmt::Formatter) -> fmt::Result> fmt::Display for Serialized<F> {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result { (self.0)(f) }
+        }
+
+        Serialized(serialize).to_string()
+    }
+
+    // Deserializes the string as borrowed, transient and owned string.
+    macro_rules! deserialize_all {
+        ($module:ident, $s:expr) => {
+            [
+                $module::deserialize(BorrowedStrDeserializer::<Error>::new($s)),
+                $module::deserialize(IntoDeserializer::<'_, Error>::into_deserializer($s)),
+                $module::deserialize(IntoDeserializer::<'_, Error>::into_deserializer(
+                    String::from($s),
+                )),
+            ]
+        };
+    }
+
+    #[test]
+    fn scalar_round_trip() {
+        let hex = "fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364140";
+        let scalar = Scalar::MAX;
+        assert_eq!(serialize(|f| scalar_as_hex::serialize(&scalar, f)), hex);
+
+        let prefixed = ["0x", hex].concat();
+        let uppercase = hex.to_uppercase();
+        for input in [hex, prefixed.as_str(), uppercase.as_str()] {
+            for result in deserialize_all!(scalar_as_hex, input) {
+                assert!(result.unwrap() == scalar);
+            }
+        }
+
+        let out_of_range = "fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141";
+        for input in [out_of_range, "0x01", "zz"] {
+            for result in deserialize_all!(scalar_as_hex, input) {
+                assert!(result.is_err());
+            }
+        }
+    }
+
+    #[test]
+    fn pubkey_round_trip() {
+        let compressed = "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+        let uncompressed = "0479be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798\
+                            483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8";
+
+        let key = compressed.parse::<CompressedPublicKey>().unwrap();
+        assert_eq!(serialize(|f| pubkey_as_hex::serialize(&key, f)), compressed);
+        let prefixed = ["0x", compressed].concat();
+        for input in [compressed, prefixed.as_str()] {
+            let results: [Result<CompressedPublicKey, _>; 3] =
+                deserialize_all!(pubkey_as_hex, input);
+            for result in results {
+                assert_eq!(result.unwrap(), key);
+            }
+        }
+        let results: [Result<CompressedPublicKey, _>; 3] =
+            deserialize_all!(pubkey_as_hex, uncompressed);
+        for result in results {
+            assert!(result.is_err());
+        }
+
+        let key = uncompressed.parse::<LegacyPublicKey>().unwrap();
+        assert_eq!(
+            serialize(|f| pubkey_as_hex::serialize(&key, f)),
+            uncompressed
+        );
+        let prefixed = ["0X", uncompressed].concat();
+        let results: [Result<LegacyPublicKey, _>; 3] =
+            deserialize_all!(pubkey_as_hex, prefixed.as_str());
+        for result in results {
+            assert_eq!(result.unwrap(), key);
+        }
+
+        let key = compressed.parse::<AnyPublicKey>().unwrap();
+        assert_eq!(serialize(|f| pubkey_as_hex::serialize(&key, f)), compressed);
+        let results: [Result<AnyPublicKey, _>; 3] = deserialize_all!(pubkey_as_hex, compressed);
+        for result in results {
+            assert_eq!(result.unwrap(), key);
+        }
+    }
+}
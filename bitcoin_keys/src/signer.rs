@@ -0,0 +1,164 @@
+//! Abstraction over signing backends.
+//!
+//! Applications supporting both software keys and hardware wallets can write
+//! their signing code generically over [`Signer`]. Software keys are used
+//! through [`SoftwareSigner`] which supports the signature scheme the key is
+//! intended for: ECDSA for [`Compressed`] keys and BIP-340 Schnorr for
+//! [`XOnlyKeyPair`]. Attempting to sign using the other scheme returns
+//! [`SignerError::Unsupported`] so the separation of domains is kept even in
+//! generic code.
+
+use core::fmt;
+
+use secp256k1::{ecdsa, schnorr, Message, Secp256k1, Signing};
+
+use crate::legacy::Compressed;
+use crate::XOnlyKeyPair;
+
+/// Backend able to produce signatures.
+///
+/// Both methods return [`SignerError::Unsupported`] by default so
+/// implementors only need to provide the schemes they support.
+pub trait Signer {
+    /// Signs the message using ECDSA.
+    ///
+    /// The signature must be low-S.
+    fn sign_ecdsa(&self, message: &Message) -> Result<ecdsa::Signature, SignerError> {
+        let _ = message;
+        Err(SignerError::Unsupported)
+    }
+
+    /// Signs the message using BIP-340 Schnorr signature.
+    fn sign_schnorr(&self, message: &Message) -> Result<schnorr::Signature, SignerError> {
+        let _ = message;
+        Err(SignerError::Unsupported)
+    }
+}
+
+/// Signer using a key held in memory.
+///
+/// The signer borrows the context instead of creating a temporary one so that
+/// the application can randomize it, keeping the blinding that protects the
+/// secret against side-channel attacks.
+///
+/// Schnorr signatures use auxiliary randomness obtained by calling `aux_rand`
+/// for each signature as recommended by BIP-340. ECDSA nonces are derived
+/// deterministically (RFC6979) so the ECDSA signer doesn't need the source and
+/// `()` can be passed instead.
+pub struct SoftwareSigner<'a, C: Signing, K, R> {
+    context: &'a Secp256k1<C>,
+    key: K,
+    aux_rand: R,
+}
+
+impl<'a, C: Signing, K, R> SoftwareSigner<'a, C, K, R> {
+    /// Creates the signer from a context, a key and a source of auxiliary
+    /// randomness.
+    #[inline]
+    pub fn new(context: &'a Secp256k1<C>, key: K, aux_rand: R) -> Self {
+        SoftwareSigner {
+            context,
+            key,
+            aux_rand,
+        }
+    }
+
+    /// Borrows the key used for signing.
+    #[inline]
+    pub fn key(&self) -> &K { &self.key }
+}
+
+impl<C: Signing, R> Signer for SoftwareSigner<'_, C, Compressed<secp256k1::SecretKey>, R> {
+    fn sign_ecdsa(&self, message: &Message) -> Result<ecdsa::Signature, SignerError> {
+        Ok(self.context.sign_ecdsa(message, self.key.as_inner()))
+    }
+}
+
+impl<C: Signing, R: Fn() -> [u8; 32]> Signer for SoftwareSigner<'_, C, XOnlyKeyPair, R> {
+    fn sign_schnorr(&self, message: &Message) -> Result<schnorr::Signature, SignerError> {
+        let aux_rand = (self.aux_rand)();
+        Ok(self
+            .context
+            .sign_schnorr_with_aux_rand(message, self.key.as_inner(), &aux_rand))
+    }
+}
+
+/// Returned when a [`Signer`] fails to produce the signature.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum SignerError {
+    /// The signer doesn't support the requested signature scheme.
+    Unsupported,
+    /// The signer refused to sign, e.g. the user rejected it on the device.
+    Rejected,
+    /// The signer is not available, e.g. the device is disconnected.
+    Unavailable,
+}
+
+impl fmt::Display for SignerError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SignerError::Unsupported => {
+                f.write_str("the signer doesn't support the signature scheme")
+            }
+            SignerError::Rejected => f.write_str("the signer refused to sign"),
+            SignerError::Unavailable => f.write_str("the signer is not available"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+#[cfg_attr(docsrs, doc(cfg(feature = "std")))]
+impl std::error::Error for SignerError {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn sign_both<S: Signer>(
+        signer: &S,
+        message: &Message,
+    ) -> (
+        Result<ecdsa::Signature, SignerError>,
+        Result<schnorr::Signature, SignerError>,
+    ) {
+        (signer.sign_ecdsa(message), signer.sign_schnorr(message))
+    }
+
+    #[test]
+    fn software_signers() {
+        let mut context = Secp256k1::new();
+        context.seeded_randomize(&[3; 32]);
+        let secret = secp256k1::SecretKey::from_slice(&[42; 32]).unwrap();
+        let message = Message::from_slice(&[1; 32]).unwrap();
+
+        let signer = SoftwareSigner::new(&context, Compressed::from_raw(secret), ());
+        let (signature, schnorr) = sign_both(&signer, &message);
+        let signature = signature.unwrap();
+        assert!(crate::ecdsa::is_low_s(&signature));
+        let public_key = secret.public_key(&context);
+        assert!(context
+            .verify_ecdsa(&message, &signature, &public_key)
+            .is_ok());
+        assert!(matches!(schnorr, Err(SignerError::Unsupported)));
+
+        let keypair =
+            XOnlyKeyPair::from_raw(crate::SecpKeyPair::from_secret_key(&context, &secret));
+        let signer = SoftwareSigner::new(&context, keypair, || [7; 32]);
+        let (ecdsa, signature) = sign_both(&signer, &message);
+        assert!(matches!(ecdsa, Err(SignerError::Unsupported)));
+        let signature = signature.unwrap();
+        assert!(context
+            .verify_schnorr(&signature, &message, &keypair.public_key())
+            .is_ok());
+        // the auxiliary randomness is actually used
+        assert_eq!(
+            signature,
+            context.sign_schnorr_with_aux_rand(&message, keypair.as_inner(), &[7; 32])
+        );
+        assert_ne!(
+            signature,
+            context.sign_schnorr_no_aux_rand(&message, keypair.as_inner())
+        );
+    }
+}
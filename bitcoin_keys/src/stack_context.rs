@@ -0,0 +1,38 @@
+//! Temporary secp256k1 contexts allocated on the stack.
+//!
+//! libsecp256k1 uses static precomputed tables so the contexts are small and
+//! can be created without an allocator or a source of randomness. The contexts
+//! are not randomized so they must not be used to handle secrets on devices
+//! that may be physically accessible to an attacker.
+
+use secp256k1::ffi::types::AlignedType;
+use secp256k1::{Secp256k1, SignOnlyPreallocated, VerifyOnlyPreallocated};
+
+/// Number of aligned words in the stack buffer.
+const BUF_LEN: usize = 32;
+
+/// Calls `f` with a signing context stored in a stack buffer.
+pub(crate) fn with_signing_context<R>(f: impl FnOnce(&Secp256k1<SignOnlyPreallocated>) -> R) -> R {
+    let mut buf = [AlignedType::ZERO; BUF_LEN];
+    assert!(
+        Secp256k1::preallocate_signing_size() <= BUF_LEN,
+        "the stack buffer is too small for the signing context"
+    );
+    let context =
+        Secp256k1::preallocated_signing_only(&mut buf).expect("the buffer size was checked above");
+    f(&context)
+}
+
+/// Calls `f` with a verification context stored in a stack buffer.
+pub(crate) fn with_verification_context<R>(
+    f: impl FnOnce(&Secp256k1<VerifyOnlyPreallocated>) -> R,
+) -> R {
+    let mut buf = [AlignedType::ZERO; BUF_LEN];
+    assert!(
+        Secp256k1::preallocate_verification_size() <= BUF_LEN,
+        "the stack buffer is too small for the verification context"
+    );
+    let context = Secp256k1::preallocated_verification_only(&mut buf)
+        .expect("the buffer size was checked above");
+    f(&context)
+}